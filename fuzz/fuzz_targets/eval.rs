@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    // For any string that scans successfully, evaluating it may or may not
+    // succeed, but it should not crash, hang, or panic.
+    if let Ok(sentence) = rsj::scan::scan_sentence(data) {
+        let mut session = rsj::eval::Session::new();
+        let _ = session.eval_sentence(&sentence);
+    }
+});
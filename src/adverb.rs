@@ -0,0 +1,266 @@
+// Copyright 2022 Martin Pool
+
+//! J adverbs, which take one operand, a verb or noun, on their left, and
+//! produce a new (usually verb) word.
+//!
+//! See <https://code.jsoftware.com/wiki/Vocabulary/Glossary#Adverb>.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::rc::Rc;
+
+use bstr::BStr;
+
+use crate::array::Array;
+use crate::error::{Error, Result};
+use crate::noun::Noun;
+use crate::verb::{dyad_at_rank, Rank, Verb};
+use crate::word::Word;
+
+/// A builtin adverb, such as `/.`.
+pub struct Adverb(&'static [u8], fn(&Word) -> Result<Word>);
+
+pub const ADVERBS: &[Adverb] = &[
+    Adverb(b"/", insert),
+    Adverb(b"/.", key),
+    Adverb(b"\\", prefix_infix),
+    Adverb(b"~", reflex),
+];
+
+impl Adverb {
+    pub fn name(&self) -> &'static BStr {
+        self.0.into()
+    }
+
+    pub fn by_name<S>(s: &S) -> Option<&'static Adverb>
+    where
+        S: AsRef<[u8]>,
+    {
+        let s = s.as_ref();
+        ADVERBS.iter().find(|a| a.0 == s)
+    }
+
+    /// Apply the adverb to its operand.
+    pub fn apply(&self, u: &Word) -> Result<Word> {
+        (self.1)(u)
+    }
+}
+
+impl fmt::Debug for Adverb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Adverb")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+impl fmt::Display for Adverb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// `u/`: insert, placing u between the items of the argument.
+fn insert(u: &Word) -> Result<Word> {
+    match u {
+        Word::Verb(u) => Ok(Word::Verb(Rc::new(Insert { u: u.clone() }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u/`.
+#[derive(Debug)]
+struct Insert {
+    u: Rc<dyn Verb>,
+}
+
+impl Verb for Insert {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}/", self.u.display()).into()
+    }
+
+    /// `u/ y`: apply u between the items of y, evaluating from the right, so
+    /// that `+/ 1 2 3` is `1 + 2 + 3`.
+    ///
+    /// If y has no items, the result is the identity element of u, in the
+    /// shape of an item of y.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let mut items: Box<dyn Iterator<Item = Noun>> = match y {
+            Noun::Atom(_) => Box::new(std::iter::once(y.clone())),
+            Noun::Array(a) => Box::new(a.iter_items().rev()),
+        };
+        match items.next() {
+            Some(last) => items.try_fold(last, |acc, item| self.u.dyad(&item, &acc)),
+            None => {
+                let identity = self.u.identity().ok_or_else(|| {
+                    Error::Unimplemented("identity element for u/ of an empty list".into())
+                })?;
+                let item_shape = &y.dims()[1..];
+                if item_shape.is_empty() {
+                    Ok(Noun::Atom(identity))
+                } else {
+                    let n = item_shape.iter().product();
+                    Array::from_shape_vec(item_shape, vec![identity; n]).map(Noun::Array)
+                }
+            }
+        }
+    }
+
+    fn dyad(&self, _x: &Noun, _y: &Noun) -> Result<Noun> {
+        Err(Error::Unimplemented("table x u/ y".into()))
+    }
+}
+
+/// `u/.`: key, applying u to groups of items.
+fn key(u: &Word) -> Result<Word> {
+    match u {
+        Word::Verb(u) => Ok(Word::Verb(Rc::new(Key { u: u.clone() }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u/.`.
+#[derive(Debug)]
+struct Key {
+    u: Rc<dyn Verb>,
+}
+
+impl Verb for Key {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}/.", self.u.display()).into()
+    }
+
+    fn monad(&self, _y: &Noun) -> Result<Noun> {
+        Err(Error::Unimplemented("oblique u/. y".into()))
+    }
+
+    /// `x u/. y`: collect the items of y into groups that have the same key
+    /// in the corresponding item of x, and apply u to each group.
+    ///
+    /// The groups are in the order in which their keys first occur in x.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let keys = x.items();
+        let items = y.items();
+        if keys.len() != items.len() {
+            return Err(Error::Length);
+        }
+        let mut groups: Vec<(&Noun, Vec<Noun>)> = Vec::new();
+        for (key, item) in keys.iter().zip(items) {
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((key, vec![item])),
+            }
+        }
+        let results = groups
+            .into_iter()
+            .map(|(_, group)| {
+                let group = Noun::assemble(&[group.len()], &group)?;
+                self.u.monad(&group)
+            })
+            .collect::<Result<Vec<Noun>>>()?;
+        Noun::assemble(&[results.len()], &results)
+    }
+}
+
+/// `u\\`: prefix, applying u to each prefix of the argument, or infix, applying
+/// u to each window of x items.
+fn prefix_infix(u: &Word) -> Result<Word> {
+    match u {
+        Word::Verb(u) => Ok(Word::Verb(Rc::new(PrefixInfix { u: u.clone() }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u\\`.
+#[derive(Debug)]
+struct PrefixInfix {
+    u: Rc<dyn Verb>,
+}
+
+impl PrefixInfix {
+    /// Apply u to each of the runs of items of y given as (start, length)
+    /// pairs, and assemble the results into a list.
+    fn apply_to_runs<I>(&self, items: &[Noun], runs: I) -> Result<Noun>
+    where
+        I: Iterator<Item = (usize, usize)>,
+    {
+        let results = runs
+            .map(|(start, len)| {
+                let run = Noun::assemble(&[len], &items[start..start + len])?;
+                self.u.monad(&run)
+            })
+            .collect::<Result<Vec<Noun>>>()?;
+        Noun::assemble(&[results.len()], &results)
+    }
+}
+
+impl Verb for PrefixInfix {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}\\", self.u.display()).into()
+    }
+
+    /// `u\\ y`: apply u to each prefix of y: the first item, the first two
+    /// items, and so on.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let items = y.items();
+        self.apply_to_runs(&items, (1..=items.len()).map(|len| (0, len)))
+    }
+
+    /// `x u\\ y`: apply u to each overlapping window of x items of y, or if
+    /// x is negative, to each of the non-overlapping runs of -x items, the
+    /// last of which may be shorter.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        dyad_at_rank(x, y, (Rank::Finite(0), Rank::Infinite), |x, y| {
+            let x = match x {
+                Noun::Atom(a) => a.try_to_f64().ok_or(Error::Domain)?,
+                Noun::Array(_) => return Err(Error::Domain),
+            };
+            if x.fract() != 0.0 || x.is_infinite() {
+                return Err(Error::Domain);
+            }
+            let items = y.items();
+            let n = items.len();
+            let width = x.abs() as usize;
+            if x >= 0.0 {
+                let count = (n + 1).saturating_sub(width);
+                self.apply_to_runs(&items, (0..count).map(|start| (start, width)))
+            } else {
+                let runs = (0..n)
+                    .step_by(width)
+                    .map(|start| (start, width.min(n - start)));
+                self.apply_to_runs(&items, runs)
+            }
+        })
+    }
+}
+
+/// `u~`: reflex, applying u with y as both arguments, or passive, swapping
+/// the arguments.
+fn reflex(u: &Word) -> Result<Word> {
+    match u {
+        Word::Verb(u) => Ok(Word::Verb(Rc::new(Reflex { u: u.clone() }))),
+        _ => Err(Error::Unimplemented("evoke m~".into())),
+    }
+}
+
+/// A verb derived from `u~`.
+#[derive(Debug)]
+struct Reflex {
+    u: Rc<dyn Verb>,
+}
+
+impl Verb for Reflex {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}~", self.u.display()).into()
+    }
+
+    /// `u~ y`: reflex: `y u y`.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.u.dyad(y, y)
+    }
+
+    /// `x u~ y`: passive: `y u x`.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        self.u.dyad(y, x)
+    }
+}
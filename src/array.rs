@@ -2,14 +2,15 @@
 
 //! Array objects.
 
-use std::fmt;
+use std::fmt::{self, Write};
 
 use ndarray::prelude::*;
 
 use crate::atom::Atom;
+use crate::error::{Error, Result};
+use crate::noun::Noun;
 
-/// Arrays potentially have n dimensions, although only 1-dimensional arrays are
-/// supported for now.
+/// Arrays potentially have n dimensions.
 ///
 /// Arrays are backed by an ndarray array.
 ///
@@ -18,34 +19,170 @@ use crate::atom::Atom;
 /// Arrays are always homogenous: all the atoms must be of the same kind.
 /// At present, each item is individually an Atom enum, but potentially the
 /// discriminant could be lifted to the level of the Array.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Array(ArrayD<Atom>);
+///
+/// An empty array has no atoms to show its type, so it can instead remember
+/// the fill atom of the type it was made from, such as a space for `''`.
+#[derive(Debug, Clone)]
+pub struct Array {
+    atoms: ArrayD<Atom>,
+    /// The fill for the type of the atoms, if it's known and the array may be
+    /// empty.
+    fill: Option<Atom>,
+}
+
+/// Arrays are equal if they have the same shape and atoms: empty arrays of
+/// the same shape are equal whatever type they were made from.
+impl PartialEq for Array {
+    fn eq(&self, other: &Array) -> bool {
+        self.atoms == other.atoms
+    }
+}
 
 impl Array {
+    /// Wrap an ndarray of atoms, whose fill is not yet known.
+    fn new(atoms: ArrayD<Atom>) -> Array {
+        Array { atoms, fill: None }
+    }
+
     /// Construct an array by taking ownership of a Vec of Atoms.
     #[must_use]
     pub fn from_vec(v: Vec<Atom>) -> Array {
-        Array(Array1::from(v).into_dyn())
+        Array::new(Array1::from(v).into_dyn())
+    }
+
+    /// Construct an array with the given shape, from atoms in row-major order.
+    ///
+    /// Returns [Error::Length] if the number of atoms doesn't match the shape.
+    pub fn from_shape_vec(shape: &[usize], data: Vec<Atom>) -> Result<Array> {
+        ArrayD::from_shape_vec(IxDyn(shape), data)
+            .map(Array::new)
+            .map_err(|_| Error::Length)
     }
 
     /// Iterate by-reference the atoms in the array.
-    pub fn iter_atoms<'a>(&'a self) -> impl Iterator<Item = &Atom> + 'a {
+    pub fn iter_atoms(&self) -> impl Iterator<Item = &Atom> + '_ {
         self.into_iter()
     }
 
     /// Return the number of _items_ in the array: the cells whose rank is one lower than the rank of the
     /// array.
-    ///
-    /// Since only 1d arrays are supported at the moment this is just the atoms.
     #[must_use]
     pub fn number_items(&self) -> usize {
-        self.0.len()
+        self.atoms.shape().first().copied().unwrap_or(1)
+    }
+
+    /// Iterate the items of the array: the cells along the leading axis.
+    pub fn iter_items(&self) -> impl DoubleEndedIterator<Item = Noun> + ExactSizeIterator + '_ {
+        self.atoms
+            .outer_iter()
+            .map(|item| Noun::from(Array::new(item.to_owned()).with_fill_of(self)))
+    }
+
+    /// True if this array contains characters.
+    ///
+    /// An empty array has no atoms, and so is not known to contain characters.
+    #[must_use]
+    pub fn is_char(&self) -> bool {
+        self.atoms.iter().next().is_some_and(Atom::is_char)
+    }
+
+    /// True if this array contains boxes.
+    #[must_use]
+    pub fn is_boxed(&self) -> bool {
+        self.atoms.iter().next().is_some_and(Atom::is_boxed)
+    }
+
+    /// Return the length of each axis.
+    #[must_use]
+    pub fn dims(&self) -> &[usize] {
+        self.atoms.shape()
+    }
+
+    /// Apply a function to every atom, returning an array of the same shape.
+    pub fn try_map<F>(&self, f: F) -> Result<Array>
+    where
+        F: FnMut(&Atom) -> Result<Atom>,
+    {
+        let atoms = self.atoms.iter().map(f).collect::<Result<Vec<Atom>>>()?;
+        Ok(Array::new(
+            ArrayD::from_shape_vec(self.atoms.raw_dim(), atoms)
+                .expect("shape matches source array"),
+        ))
     }
 
     /// Return the shape of the array, as another array.
     #[must_use]
     pub fn shape(&self) -> Array {
-        self.0.shape().iter().map(|&s| Atom::from(s)).collect()
+        self.atoms.shape().iter().map(|&s| Atom::from(s)).collect()
+    }
+
+    /// Return a new array whose axis `i` is axis `axes[i]` of this array.
+    ///
+    /// `axes` must be a permutation of the axis numbers of the array.
+    #[must_use]
+    pub fn permuted_axes(&self, axes: &[usize]) -> Array {
+        Array::new(
+            self.atoms
+                .clone()
+                .permuted_axes(IxDyn(axes))
+                .as_standard_layout()
+                .into_owned(),
+        )
+        .with_fill_of(self)
+    }
+
+    /// Return a new array of the given shape, filled with the atoms of this
+    /// array in order, repeated cyclically if there are too few, or
+    /// truncated if there are too many.
+    ///
+    /// An empty array is filled with its fill atom. Returns [Error::OutOfMemory] if
+    /// the new array would be too large, counting each empty axis as if it
+    /// had length 1, since arrays with some empty axes can still have very
+    /// many cells.
+    pub fn reshape(&self, shape: &[usize]) -> Result<Array> {
+        if shape
+            .iter()
+            .try_fold(1usize, |acc, &d| acc.checked_mul(d.max(1)))
+            .is_none_or(|n| n > crate::ARRAY_SIZE_LIMIT)
+        {
+            return Err(Error::OutOfMemory);
+        }
+        let len = shape.iter().product::<usize>();
+        let atoms: Vec<Atom> = if self.atoms.is_empty() {
+            vec![self.fill(); len]
+        } else {
+            self.atoms.iter().cycle().take(len).cloned().collect()
+        };
+        Array::from_shape_vec(shape, atoms).map(|a| a.with_fill_of(self))
+    }
+
+    /// The atom used to pad this array: the fill for the type of its atoms,
+    /// or if it's empty, for the type it was made from, or 0 if that's not
+    /// known.
+    #[must_use]
+    pub fn fill(&self) -> Atom {
+        match (self.atoms.iter().next(), &self.fill) {
+            (Some(atom), _) => atom.fill(),
+            (None, Some(fill)) => fill.clone(),
+            (None, None) => Atom::zero(),
+        }
+    }
+
+    /// Remember `fill` as the fill atom of this array, so that it keeps its
+    /// type even if it's empty.
+    #[must_use]
+    pub fn with_fill(self, fill: Atom) -> Array {
+        Array {
+            fill: Some(fill),
+            ..self
+        }
+    }
+
+    /// Give this array the same fill as `other`, which it was made from.
+    #[must_use]
+    pub(crate) fn with_fill_of(self, other: &Array) -> Array {
+        let fill = other.fill();
+        self.with_fill(fill)
     }
 
     /// Return an empty (1-d) array.
@@ -61,7 +198,7 @@ impl<'a> IntoIterator for &'a Array {
     type IntoIter = ndarray::iter::Iter<'a, Atom, IxDyn>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.atoms.iter()
     }
 }
 
@@ -74,7 +211,7 @@ where
     where
         I: IntoIterator<Item = T>,
     {
-        Array(
+        Array::new(
             iter.into_iter()
                 .map(Atom::from)
                 .collect::<Array1<_>>()
@@ -96,10 +233,27 @@ where
 
 impl fmt::Display for Array {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // TODO: Handle multi-dimensional arrays.
+        if self.is_boxed() {
+            return self.fmt_boxes(f);
+        }
+        if self.atoms.ndim() > 1 {
+            return self.fmt_table(f);
+        }
         let limit = f.precision();
+        if self.is_char() {
+            let s: String = self.atoms.iter().map(Atom::to_string).collect();
+            return match limit {
+                Some(limit) if self.atoms.len() > limit => {
+                    // Characters above 127 take more than one byte in the
+                    // string, so truncate by characters, not bytes.
+                    let truncated: String = s.chars().take(limit.saturating_sub(3)).collect();
+                    write!(f, "{}...", truncated)
+                }
+                _ => f.write_str(&s),
+            };
+        }
         let mut col = 0;
-        for (i, atom) in self.0.iter().enumerate() {
+        for (i, atom) in self.atoms.iter().enumerate() {
             if i > 0 {
                 f.write_str(" ")?;
                 col += 1;
@@ -117,3 +271,232 @@ impl fmt::Display for Array {
         Ok(())
     }
 }
+
+/// When a width limit is given, tables are cut short after this many rows.
+const MAX_TABLE_ROWS: usize = 100;
+
+impl Array {
+    /// Format an array of rank 2 or more as a table.
+    ///
+    /// Numbers in each column are aligned on their decimal points, and the
+    /// column is right-aligned to the width of its widest atom. Arrays of
+    /// rank 3 or more are shown as a series of tables, separated by one blank
+    /// line for each axis beyond the second.
+    ///
+    /// If the formatter has a precision, it's used as a width limit like for
+    /// lists: columns that don't fit are replaced by `...`, and after
+    /// [MAX_TABLE_ROWS] rows a final line of `...` is shown.
+    fn fmt_table(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shape = self.atoms.shape();
+        let ncols = shape[shape.len() - 1];
+        let nrows = shape[shape.len() - 2];
+        let mut strs: Vec<String> = self.atoms.iter().map(Atom::to_string).collect();
+        // Characters are shown in rows without any separators.
+        let separator = if self.is_char() { "" } else { " " };
+        if !self.is_char() {
+            // Align the numbers in each column on their decimal points, by
+            // padding those with shorter fractions on the right.
+            let mut fractions = vec![0; ncols];
+            for (i, s) in strs.iter().enumerate() {
+                fractions[i % ncols] = fractions[i % ncols].max(fraction_len(s));
+            }
+            for (i, s) in strs.iter_mut().enumerate() {
+                let pad = fractions[i % ncols] - fraction_len(s);
+                s.extend(std::iter::repeat_n(' ', pad));
+            }
+        }
+        let mut widths = vec![0; ncols];
+        for (i, s) in strs.iter().enumerate() {
+            widths[i % ncols] = widths[i % ncols].max(s.len());
+        }
+        let limit = f.precision();
+        // Show as many columns as fit in the limit, leaving room for the
+        // separator and `...`, but always at least one.
+        let mut shown_cols = ncols;
+        if let Some(limit) = limit {
+            let row_width =
+                widths.iter().sum::<usize>() + separator.len() * ncols.saturating_sub(1);
+            if row_width > limit {
+                let ellipsis = separator.len() + 3;
+                let mut width = 0;
+                shown_cols = widths
+                    .iter()
+                    .take_while(|&&w| {
+                        width += w + separator.len();
+                        width - separator.len() + ellipsis <= limit
+                    })
+                    .count()
+                    .max(1);
+            }
+        }
+        let total_rows = strs.len().checked_div(ncols).unwrap_or(0);
+        for row in 0..total_rows {
+            if row > 0 {
+                f.write_str("\n")?;
+                if limit.is_some() && row == MAX_TABLE_ROWS {
+                    return f.write_str("...");
+                }
+                // Separate tables by one line per additional axis that rolled
+                // over.
+                let mut stride = nrows;
+                for &dim in shape[..shape.len() - 2].iter().rev() {
+                    if row % stride == 0 {
+                        f.write_str("\n")?;
+                    }
+                    stride *= dim;
+                }
+            }
+            let mut line = String::new();
+            for (col, width) in widths.iter().enumerate().take(shown_cols) {
+                if col > 0 {
+                    line.push_str(separator);
+                }
+                write!(line, "{:>width$}", strs[row * ncols + col], width = width)?;
+            }
+            if shown_cols < ncols {
+                write!(line, "{}...", separator)?;
+            }
+            // Spaces in a row of characters are part of the text, but padding
+            // after the last number is dropped.
+            if self.is_char() {
+                f.write_str(&line)?;
+            } else {
+                f.write_str(line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The length of the fractional part of a formatted number, including the
+/// decimal point, or 0 if it has none.
+fn fraction_len(s: &str) -> usize {
+    s.find('.').map_or(0, |i| s.len() - i)
+}
+
+impl Array {
+    /// Format an array of boxes as a grid.
+    ///
+    /// A list is shown as a single row of boxes, and a table as rows of boxes.
+    /// Arrays of rank 3 or more are shown as a series of grids, separated by
+    /// blank lines.
+    fn fmt_boxes(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shape = self.atoms.shape();
+        let ncols = shape[shape.len() - 1];
+        let plane_len = shape[shape.len().saturating_sub(2)..]
+            .iter()
+            .product::<usize>();
+        let contents: Vec<String> = self
+            .atoms
+            .iter()
+            .map(|atom| match atom {
+                Atom::Boxed(noun) => box_contents(f, noun),
+                other => other.to_string(),
+            })
+            .collect();
+        for (i, plane) in contents.chunks(plane_len.max(1)).enumerate() {
+            if i > 0 {
+                f.write_str("\n\n")?;
+            }
+            fmt_box_grid(f, ncols, plane)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format the contents of a box, drawing any boxes within it in the same style
+/// as the outer box.
+pub(crate) fn box_contents(f: &fmt::Formatter<'_>, noun: &Noun) -> String {
+    if f.alternate() {
+        format!("{:#}", noun)
+    } else {
+        noun.to_string()
+    }
+}
+
+/// Characters for drawing boxes: the corners and junctions for the top, middle
+/// and bottom border lines, and the horizontal and vertical lines.
+struct BoxChars {
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+    horizontal: char,
+    vertical: char,
+}
+
+const UNICODE_BOX_CHARS: BoxChars = BoxChars {
+    top: ['┌', '┬', '┐'],
+    middle: ['├', '┼', '┤'],
+    bottom: ['└', '┴', '┘'],
+    horizontal: '─',
+    vertical: '│',
+};
+
+const ASCII_BOX_CHARS: BoxChars = BoxChars {
+    top: ['+', '+', '+'],
+    middle: ['+', '+', '+'],
+    bottom: ['+', '+', '+'],
+    horizontal: '-',
+    vertical: '|',
+};
+
+/// Draw a grid of boxes around the given contents, `ncols` boxes to a row.
+///
+/// Each column is as wide as its widest line, and each row as tall as its
+/// tallest contents. Contents are aligned to the top left of each box.
+///
+/// Boxes are drawn with Unicode box-drawing characters, or with ASCII if the
+/// formatter's alternate flag is set.
+pub(crate) fn fmt_box_grid(
+    f: &mut fmt::Formatter<'_>,
+    ncols: usize,
+    contents: &[String],
+) -> fmt::Result {
+    if ncols == 0 || contents.is_empty() {
+        return Ok(());
+    }
+    let cells: Vec<Vec<&str>> = contents.iter().map(|c| c.lines().collect()).collect();
+    let mut widths = vec![0; ncols];
+    for (i, lines) in cells.iter().enumerate() {
+        for line in lines {
+            widths[i % ncols] = widths[i % ncols].max(line.chars().count());
+        }
+    }
+    let chars = if f.alternate() {
+        &ASCII_BOX_CHARS
+    } else {
+        &UNICODE_BOX_CHARS
+    };
+    let border = |f: &mut fmt::Formatter<'_>, [left, mid, right]: [char; 3]| {
+        f.write_char(left)?;
+        for (col, width) in widths.iter().enumerate() {
+            if col > 0 {
+                f.write_char(mid)?;
+            }
+            for _ in 0..*width {
+                f.write_char(chars.horizontal)?;
+            }
+        }
+        f.write_char(right)
+    };
+    border(f, chars.top)?;
+    for (row, row_cells) in cells.chunks(ncols).enumerate() {
+        if row > 0 {
+            f.write_char('\n')?;
+            border(f, chars.middle)?;
+        }
+        // Even an empty box has one line inside it.
+        let height = row_cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        for i in 0..height {
+            f.write_char('\n')?;
+            f.write_char(chars.vertical)?;
+            for (lines, width) in row_cells.iter().zip(&widths) {
+                let line = lines.get(i).copied().unwrap_or("");
+                write!(f, "{}{}", line, " ".repeat(width - line.chars().count()))?;
+                f.write_char(chars.vertical)?;
+            }
+        }
+    }
+    f.write_char('\n')?;
+    border(f, chars.bottom)
+}
@@ -2,15 +2,28 @@
 
 //! Nouns (J objects.)
 
-use std::convert::TryInto;
 use std::fmt::{self, Write};
 
 use num_complex::Complex64;
 
+use crate::array::{box_contents, fmt_box_grid, Array};
+use crate::error::{Error, Result};
+use crate::noun::Noun;
+
+/// The largest magnitude, 2^53, up to which every integer is exactly
+/// represented.
+///
+/// Numbers are stored as floats, so larger integers may be rounded: for
+/// example 2^53 + 1 is rounded to 2^53.
+pub const EXACT_INTEGER_LIMIT: f64 = 9_007_199_254_740_992.0;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Atom {
     Complex(Complex64),
-    // TODO: char, ...
+    /// A character, which in J is a single byte.
+    Char(u8),
+    /// A box, containing any noun.
+    Boxed(Box<Noun>),
 }
 
 impl Atom {
@@ -18,18 +31,78 @@ impl Atom {
         Atom::Complex(0.0.into())
     }
 
-    pub fn to_complex(&self) -> Complex64 {
+    /// Return the numeric value of this atom, or a domain error if it's not a number.
+    pub fn to_complex(&self) -> Result<Complex64> {
         match self {
-            Atom::Complex(a) => *a,
+            Atom::Complex(a) => Ok(*a),
+            Atom::Char(_) | Atom::Boxed(_) => Err(Error::Domain),
         }
     }
 
     pub fn is_zero(&self) -> bool {
         match self {
             Atom::Complex(Complex64 { re, im }) => *re == 0.0 && *im == 0.0,
+            Atom::Char(_) | Atom::Boxed(_) => false,
         }
     }
 
+    pub fn is_char(&self) -> bool {
+        matches!(self, Atom::Char(_))
+    }
+
+    pub fn is_boxed(&self) -> bool {
+        matches!(self, Atom::Boxed(_))
+    }
+
+    /// The atom used to pad arrays containing atoms of this type: 0 for numbers, space for
+    /// characters, and an empty box for boxes.
+    pub fn fill(&self) -> Atom {
+        match self {
+            Atom::Complex(_) => Atom::zero(),
+            Atom::Char(_) => Atom::Char(b' '),
+            Atom::Boxed(_) => Atom::Boxed(Box::new(Noun::Array(Array::empty()))),
+        }
+    }
+
+    /// True if the two atoms are of the same type, and so can be stored in the same array.
+    pub fn same_type(&self, other: &Atom) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// True if this is a real integer that's exactly represented: that is,
+    /// its magnitude is no more than [EXACT_INTEGER_LIMIT].
+    pub fn is_exact_integer(&self) -> bool {
+        self.try_to_f64()
+            .is_some_and(|v| v.fract() == 0.0 && v.abs() <= EXACT_INTEGER_LIMIT)
+    }
+
+    /// True if this is a real number whose magnitude is more than
+    /// [EXACT_INTEGER_LIMIT], so it may have been rounded.
+    ///
+    /// Infinities are not inexact.
+    pub fn is_inexact_integer(&self) -> bool {
+        self.try_to_f64()
+            .is_some_and(|v| v.is_finite() && v.abs() > EXACT_INTEGER_LIMIT)
+    }
+
+    /// Interpret this atom as an index into a list of `len` items.
+    ///
+    /// Negative indexes count back from the end, so `_1` is the last item.
+    /// Returns [Error::Domain] if the atom is not an integer, or
+    /// [Error::Length] if it's out of range.
+    pub fn to_index(&self, len: usize) -> Result<usize> {
+        let i = self.try_to_f64().ok_or(Error::Domain)?;
+        if i.fract() != 0.0 {
+            return Err(Error::Domain);
+        }
+        let len = len as f64;
+        let i = if i < 0.0 { i + len } else { i };
+        if i < 0.0 || i >= len {
+            return Err(Error::Length);
+        }
+        Ok(i as usize)
+    }
+
     /// Return an f64 if this is representable as such.
     pub fn try_to_f64(&self) -> Option<f64> {
         match self {
@@ -40,6 +113,7 @@ impl Atom {
                     None
                 }
             }
+            Atom::Char(_) | Atom::Boxed(_) => None,
         }
     }
 }
@@ -63,9 +137,11 @@ impl From<Complex64> for Atom {
 }
 
 impl From<usize> for Atom {
+    /// Convert a count or index to a number.
+    ///
+    /// Values above 2^53 can't be exactly represented and are rounded.
     fn from(v: usize) -> Self {
-        let a: u32 = v.try_into().unwrap();
-        Atom::Complex(Complex64::new(a.into(), 0.0))
+        Atom::Complex(Complex64::new(v as f64, 0.0))
     }
 }
 
@@ -73,18 +149,18 @@ impl fmt::Display for Atom {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Atom::Complex(v) => display_complex(*v, f),
+            Atom::Char(c) => f.write_char(*c as char),
+            Atom::Boxed(noun) => fmt_box_grid(f, 1, &[box_contents(f, noun)]),
         }
     }
 }
 
+/// Write a number as J would, such as `3j_4`, with the imaginary part only if
+/// it's not zero, so that the text scans back to the same number.
 pub(crate) fn display_complex(n: Complex64, f: &mut fmt::Formatter) -> fmt::Result {
-    // TODO: Move to display of the atom?
-    let mut s = String::new();
-    if n.im == 0.0 {
-        display_f64(n.re, f)?;
-    } else {
-        display_f64(n.re, f)?;
-        write!(s, "j")?;
+    display_f64(n.re, f)?;
+    if n.im != 0.0 {
+        f.write_char('j')?;
         display_f64(n.im, f)?;
     }
     Ok(())
@@ -95,6 +171,10 @@ fn display_f64(n: f64, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_char('_')
     } else if n == f64::NEG_INFINITY {
         f.write_str("__")
+    } else if n == 0.0 {
+        // Negative zero is kept internally, so that for example `% _0` is
+        // `__`, but like J we display it as plain 0.
+        f.write_char('0')
     } else {
         let mut buffer = ryu::Buffer::new();
         let s = buffer.format(n);
@@ -2,13 +2,26 @@
 
 //! Toy J interpreter: main program.
 
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
 
 use argh::FromArgs;
 
+use rsj::word::Word;
+
 #[derive(FromArgs)]
-#[argh(description = "J language interpreter")]
+#[argh(description = "J language interpreter: with no options, starts an interactive session")]
 struct Args {
+    #[argh(switch, description = "print the version of rsj and exit")]
+    version: bool,
+
+    #[argh(
+        positional,
+        description = "a J script to run, printing the result of each sentence"
+    )]
+    script: Option<PathBuf>,
+
     #[argh(
         option,
         short = 'D',
@@ -16,10 +29,16 @@ struct Args {
     )]
     diff_markdown: Option<PathBuf>,
 
+    #[argh(
+        option,
+        description = "show a diff like -D, report on stderr how many examples differ, and exit with that count"
+    )]
+    check: Option<PathBuf>,
+
     #[argh(
         option,
         short = 'M',
-        description = "update a Markdown file containing J fragments"
+        description = "update a Markdown file containing J fragments, and report on stderr how many examples changed"
     )]
     update_markdown: Option<PathBuf>,
 
@@ -28,22 +47,168 @@ struct Args {
         description = "extract and print the J transcript from a Markdown file"
     )]
     extract_transcript: Option<PathBuf>,
+
+    #[argh(
+        switch,
+        description = "when updating Markdown, overwrite the file without keeping the original as a .old file"
+    )]
+    no_backup: bool,
+
+    #[argh(
+        switch,
+        description = "when checking or updating Markdown, keep the existing output of examples that use unimplemented features"
+    )]
+    keep_unimplemented: bool,
+
+    #[argh(
+        switch,
+        description = "in interactive use, truncate results taller than the terminal"
+    )]
+    truncate_rows: bool,
+
+    #[argh(
+        switch,
+        description = "log each verb application, with its arguments and result, to stderr"
+    )]
+    trace: bool,
+
+    #[argh(
+        switch,
+        description = "when checking or updating Markdown, show on stderr the old and new output of each sentence that changed, and the verbs applied to evaluate it"
+    )]
+    explain: bool,
+
+    #[argh(
+        option,
+        description = "in interactive use, a J script to run before the first prompt; by default $RSJ_PROFILE, or ~/.rsj_profile.ijs if it exists"
+    )]
+    profile: Option<PathBuf>,
+
+    #[argh(
+        switch,
+        description = "read sentences from stdin, one per line, and write the result of each as a line of JSON"
+    )]
+    ndjson: bool,
+
+    #[argh(
+        switch,
+        description = "in Markdown files, draw boxes with Unicode box-drawing characters rather than ASCII"
+    )]
+    unicode_boxes: bool,
 }
 
 fn main() -> rsj::error::Result<()> {
     let args: Args = argh::from_env();
+    if args.version {
+        println!("rsj {}", rsj::VERSION);
+        return Ok(());
+    }
+    let mut session = rsj::eval::Session::new();
+    session.set_keep_unimplemented(args.keep_unimplemented);
+    session.set_trace(args.trace);
+    session.set_record_trace(args.explain);
+    // The REPL has its own session, with Unicode boxes; this one is used for
+    // Markdown.
+    session.set_ascii_boxes(!args.unicode_boxes);
     if let Some(markdown_path) = args.diff_markdown {
-        let diff = rsj::markdown::diff_file(&markdown_path)?;
-        print!("{}", diff);
-        if !diff.is_empty() {
+        let check = rsj::markdown::check_file(&markdown_path, &mut session)?;
+        print!("{}", check.diff);
+        if args.explain {
+            explain(&check.changes);
+        }
+        if !check.diff.is_empty() {
             std::process::exit(1);
         }
+    } else if let Some(markdown_path) = args.check {
+        let check = rsj::markdown::check_file(&markdown_path, &mut session)?;
+        print!("{}", check.diff);
+        if args.explain {
+            explain(&check.changes);
+        }
+        eprintln!("{} of {} examples differ", check.changed, check.examples);
+        // Exit codes are only 8 bits, and must not wrap around to 0.
+        std::process::exit(check.changed.min(255) as i32);
     } else if let Some(mdpath) = args.update_markdown {
-        rsj::markdown::update_file(&mdpath)?;
+        let check = rsj::markdown::update_file(&mdpath, &mut session, !args.no_backup)?;
+        if args.explain {
+            explain(&check.changes);
+        }
+        if check.changed > 0 {
+            eprintln!(
+                "updated {} of {} examples in {}",
+                check.changed,
+                check.examples,
+                mdpath.display()
+            );
+        }
     } else if let Some(markdown_path) = args.extract_transcript {
         print!("{}", rsj::markdown::extract_transcript(&markdown_path)?);
+    } else if args.ndjson {
+        run_ndjson(&mut session)?;
+    } else if let Some(script) = args.script {
+        // Scripts print to the terminal, like the REPL, so they draw boxes
+        // the same way.
+        session.set_ascii_boxes(false);
+        if !run_script(&script, &mut session)? {
+            std::process::exit(1);
+        }
     } else {
-        rsj::repl::repl();
+        rsj::repl::repl(&rsj::repl::ReplOptions {
+            truncate_rows: args.truncate_rows,
+            trace: args.trace,
+            profile: args.profile.or_else(default_profile),
+        });
+    }
+    Ok(())
+}
+
+/// Run a J script, printing the result of each sentence that gives a noun, and
+/// each error.
+///
+/// Returns false if any sentence failed.
+fn run_script(path: &Path, session: &mut rsj::eval::Session) -> rsj::error::Result<bool> {
+    let text = fs::read_to_string(path)?;
+    let mut ok = true;
+    for result in session.eval_all(&text) {
+        ok &= result.is_ok();
+        let output = session.format_result(result.map(|noun| noun.map(Word::Noun)));
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+    }
+    Ok(ok)
+}
+
+/// Evaluate each line of stdin, writing a line of JSON describing each
+/// result to stdout as soon as it's known.
+fn run_ndjson(session: &mut rsj::eval::Session) -> rsj::error::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        writeln!(stdout, "{}", rsj::ndjson::eval_line(session, &line?))?;
+        stdout.flush()?;
     }
     Ok(())
 }
+
+/// The profile named by `$RSJ_PROFILE`, or otherwise `~/.rsj_profile.ijs` if
+/// there is one.
+fn default_profile() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RSJ_PROFILE") {
+        return Some(path.into());
+    }
+    let path = PathBuf::from(std::env::var_os("HOME")?).join(".rsj_profile.ijs");
+    path.is_file().then_some(path)
+}
+
+/// Show on stderr how each changed sentence was evaluated.
+fn explain(changes: &[rsj::transcript::Change]) {
+    for change in changes {
+        eprintln!("   {}", change.input);
+        eprintln!("expected:\n{}", change.expected);
+        eprintln!("output:\n{}", change.output);
+        for step in &change.trace {
+            eprintln!("trace: {}", step);
+        }
+        eprintln!();
+    }
+}
@@ -0,0 +1,869 @@
+// Copyright 2022 Martin Pool
+
+//! J conjunctions, which take two operands, verbs or nouns, and produce a new
+//! (usually verb) word.
+//!
+//! See <https://code.jsoftware.com/wiki/Vocabulary/Glossary#Conjunction>.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::rc::Rc;
+
+use bstr::BStr;
+
+use crate::array::Array;
+use crate::atom::Atom;
+use crate::error::{Error, Result};
+use crate::limit;
+use crate::noun::Noun;
+use crate::primitive::{from_with_fill, Primitive};
+use crate::verb::{dyad_at_rank, monad_at_rank, Rank, Verb};
+use crate::word::Word;
+
+/// A builtin conjunction, such as `"`.
+pub struct Conjunction(&'static [u8], fn(&Word, &Word) -> Result<Word>);
+
+pub const CONJUNCTIONS: &[Conjunction] = &[
+    Conjunction(b"\"", rank),
+    Conjunction(b"`", tie),
+    Conjunction(b"@.", agenda),
+    Conjunction(b"&", compose),
+    Conjunction(b"&:", appose),
+    Conjunction(b"&.", under),
+    Conjunction(b"^:", power),
+    Conjunction(b".", dot),
+    Conjunction(b";.", cut),
+    Conjunction(b"!.", fit),
+];
+
+impl Conjunction {
+    pub fn name(&self) -> &'static BStr {
+        self.0.into()
+    }
+
+    pub fn by_name<S>(s: &S) -> Option<&'static Conjunction>
+    where
+        S: AsRef<[u8]>,
+    {
+        let s = s.as_ref();
+        CONJUNCTIONS.iter().find(|c| c.0 == s)
+    }
+
+    /// Apply the conjunction to its left and right operands.
+    pub fn apply(&self, u: &Word, v: &Word) -> Result<Word> {
+        (self.1)(u, v)
+    }
+}
+
+impl fmt::Debug for Conjunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Conjunction")
+            .field("name", &self.name())
+            .finish()
+    }
+}
+
+impl fmt::Display for Conjunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// `u"n`: apply u to cells of rank n.
+///
+/// n may have one rank, which applies to the monad and both arguments of the
+/// dyad; two ranks, for the left and right dyad arguments, with the right also
+/// used for the monad; or three ranks, for the monad, and left and right dyad.
+fn rank(u: &Word, n: &Word) -> Result<Word> {
+    let n = match n {
+        Word::Noun(n) => n,
+        _ => return Err(Error::Domain),
+    };
+    let ranks = n
+        .iter_atoms()
+        .map(|a| match a.try_to_f64() {
+            Some(r) if r == f64::INFINITY => Ok(Rank::Infinite),
+            Some(r) if r >= 0.0 && r.fract() == 0.0 => Ok(Rank::Finite(r as usize)),
+            Some(r) if r == f64::NEG_INFINITY => Ok(Rank::Finite(0)),
+            Some(r) if r < 0.0 && r.fract() == 0.0 => Ok(Rank::Negative(-r as usize)),
+            _ => Err(Error::Domain),
+        })
+        .collect::<Result<Vec<Rank>>>()?;
+    let ranks = match (n.dims().len(), ranks.as_slice()) {
+        (0 | 1, &[r]) => [r, r, r],
+        (1, &[l, r]) => [r, l, r],
+        (1, &[m, l, r]) => [m, l, r],
+        (1, _) => return Err(Error::Length),
+        _ => return Err(Error::Rank),
+    };
+    let u = match u {
+        Word::Verb(v) => RankOperand::Verb(v.clone()),
+        Word::Noun(n) => RankOperand::Noun(n.clone()),
+        _ => return Err(Error::Domain),
+    };
+    Ok(Word::Verb(Rc::new(Ranked { u, ranks })))
+}
+
+#[derive(Debug)]
+enum RankOperand {
+    Verb(Rc<dyn Verb>),
+    /// A constant noun, which is the result for every cell.
+    Noun(Noun),
+}
+
+/// A verb derived from `u"n`.
+#[derive(Debug)]
+struct Ranked {
+    u: RankOperand,
+    /// Ranks of the monad, and the left and right dyad arguments.
+    ranks: [Rank; 3],
+}
+
+impl Verb for Ranked {
+    fn display(&self) -> Cow<'_, str> {
+        let u = match &self.u {
+            RankOperand::Verb(v) => v.display().into_owned(),
+            RankOperand::Noun(n) => n.to_string(),
+        };
+        let [m, l, r] = self.ranks;
+        let n = if m == l && l == r {
+            m.to_string()
+        } else if m == r {
+            format!("{} {}", l, r)
+        } else {
+            format!("{} {} {}", m, l, r)
+        };
+        format!("{}\"{}", u, n).into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        match &self.u {
+            RankOperand::Verb(u) => monad_at_rank(y, self.ranks[0], |c| u.monad(c)),
+            RankOperand::Noun(n) => monad_at_rank(y, self.ranks[0], |_| Ok(n.clone())),
+        }
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let ranks = (self.ranks[1], self.ranks[2]);
+        match &self.u {
+            RankOperand::Verb(u) => dyad_at_rank(x, y, ranks, |cx, cy| u.dyad(cx, cy)),
+            RankOperand::Noun(n) => dyad_at_rank(x, y, ranks, |_, _| Ok(n.clone())),
+        }
+    }
+
+    fn monad_rank(&self) -> Rank {
+        self.ranks[0]
+    }
+}
+
+/// `` u`v ``: a gerund containing the verbs of u followed by those of v.
+fn tie(u: &Word, v: &Word) -> Result<Word> {
+    let mut verbs = gerund_verbs(u)?;
+    verbs.extend(gerund_verbs(v)?);
+    Ok(Word::Gerund(verbs))
+}
+
+/// Return the verbs in a verb or gerund operand.
+fn gerund_verbs(w: &Word) -> Result<Vec<Rc<dyn Verb>>> {
+    match w {
+        Word::Verb(v) => Ok(vec![v.clone()]),
+        Word::Gerund(vs) => Ok(vs.clone()),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// `` m@.n ``: agenda: apply the verb from gerund m chosen by the index n.
+///
+/// The index can be a constant noun, or a verb that's applied to the arguments
+/// to choose the verb. Negative indexes count back from the end.
+fn agenda(m: &Word, n: &Word) -> Result<Word> {
+    let verbs = match m {
+        Word::Gerund(verbs) => verbs.clone(),
+        _ => return Err(Error::Domain),
+    };
+    let selector = match n {
+        Word::Noun(n) => Selector::Noun(n.clone()),
+        Word::Verb(v) => Selector::Verb(v.clone()),
+        _ => return Err(Error::Domain),
+    };
+    Ok(Word::Verb(Rc::new(Agenda { verbs, selector })))
+}
+
+#[derive(Debug)]
+enum Selector {
+    Noun(Noun),
+    Verb(Rc<dyn Verb>),
+}
+
+/// A verb derived from `` m@.n ``.
+#[derive(Debug)]
+struct Agenda {
+    verbs: Vec<Rc<dyn Verb>>,
+    selector: Selector,
+}
+
+impl Agenda {
+    /// Choose a verb given the selector's value.
+    fn choose(&self, index: &Noun) -> Result<&Rc<dyn Verb>> {
+        match index {
+            Noun::Atom(a) => Ok(&self.verbs[a.to_index(self.verbs.len())?]),
+            Noun::Array(_) => Err(Error::Unimplemented(
+                "agenda with an array of indexes".into(),
+            )),
+        }
+    }
+}
+
+impl Verb for Agenda {
+    fn display(&self) -> Cow<'_, str> {
+        let m = Word::Gerund(self.verbs.clone());
+        match &self.selector {
+            Selector::Noun(n) => format!("{}@.{}", m, n).into(),
+            Selector::Verb(v) => format!("{}@.({})", m, v.display()).into(),
+        }
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let index = match &self.selector {
+            Selector::Noun(n) => n.clone(),
+            Selector::Verb(v) => v.monad(y)?,
+        };
+        self.choose(&index)?.monad(y)
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let index = match &self.selector {
+            Selector::Noun(n) => n.clone(),
+            Selector::Verb(v) => v.dyad(x, y)?,
+        };
+        self.choose(&index)?.dyad(x, y)
+    }
+}
+
+/// `u&v`: compose: apply v to each cell of the rank of v, and then u to the
+/// result.
+fn compose(u: &Word, v: &Word) -> Result<Word> {
+    composition(u, v, false)
+}
+
+/// `u&:v`: appose: apply v to the whole argument, and then u to the result.
+fn appose(u: &Word, v: &Word) -> Result<Word> {
+    composition(u, v, true)
+}
+
+fn composition(u: &Word, v: &Word, whole: bool) -> Result<Word> {
+    match (u, v) {
+        (Word::Verb(u), Word::Verb(v)) => Ok(Word::Verb(Rc::new(Compose {
+            u: u.clone(),
+            v: v.clone(),
+            whole,
+        }))),
+        (Word::Noun(m), Word::Verb(v)) if !whole => Ok(Word::Verb(Rc::new(Bond {
+            verb: v.clone(),
+            noun: m.clone(),
+            noun_on_left: true,
+        }))),
+        (Word::Verb(u), Word::Noun(n)) if !whole => Ok(Word::Verb(Rc::new(Bond {
+            verb: u.clone(),
+            noun: n.clone(),
+            noun_on_left: false,
+        }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `m&v` or `u&n`, which bonds a noun to one side of a
+/// dyad.
+#[derive(Debug, Clone)]
+struct Bond {
+    verb: Rc<dyn Verb>,
+    noun: Noun,
+    /// True for `m&v`, where the noun is the left argument.
+    noun_on_left: bool,
+}
+
+impl Verb for Bond {
+    fn display(&self) -> Cow<'_, str> {
+        if self.noun_on_left {
+            format!("{}&{}", self.noun, self.verb.display()).into()
+        } else {
+            format!("{}&{}", self.verb.display(), self.noun).into()
+        }
+    }
+
+    /// `m&v y` is `m v y`, and `u&n y` is `y u n`.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        if self.noun_on_left {
+            self.verb.dyad(&self.noun, y)
+        } else {
+            self.verb.dyad(y, &self.noun)
+        }
+    }
+
+    /// `x m&v y` applies `m&v` to y, x times.
+    ///
+    /// Returns [Error::Limit] if x is more than [limit::max_iterations], as
+    /// for `u^:n`.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        match power(&Word::Verb(Rc::new(self.clone())), &Word::Noun(x.clone()))? {
+            Word::Verb(repeated) => repeated.monad(y),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Adding or multiplying by n is undone by subtracting or dividing by n,
+    /// and the other way around.
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        let name: &[u8] = self.verb.as_primitive().ok_or(Error::Domain)?.name();
+        let inverse: &[u8] = match (name, self.noun_on_left) {
+            (b"+", _) => b"-",
+            (b"*", _) => b"%",
+            (b"-", false) => b"+",
+            (b"%", false) => b"*",
+            _ => return Err(Error::Domain),
+        };
+        Ok(Rc::new(Bond {
+            verb: Rc::new(Primitive::by_name(&inverse)?),
+            noun: self.noun.clone(),
+            noun_on_left: false,
+        }))
+    }
+}
+
+/// A verb derived from `u&v` or `u&:v`.
+#[derive(Debug)]
+struct Compose {
+    u: Rc<dyn Verb>,
+    v: Rc<dyn Verb>,
+    /// True for `u&:v`, which applies to the whole of its arguments, rather
+    /// than to cells of the rank of v.
+    whole: bool,
+}
+
+impl Verb for Compose {
+    fn display(&self) -> Cow<'_, str> {
+        let conjunction = if self.whole { "&:" } else { "&" };
+        format!("{}{}{}", self.u.display(), conjunction, self.v.display()).into()
+    }
+
+    /// `u&v y`: `u v y`.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        monad_at_rank(y, self.monad_rank(), |y| self.u.monad(&self.v.monad(y)?))
+    }
+
+    /// `x u&v y`: `(v x) u (v y)`.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let rank = self.monad_rank();
+        dyad_at_rank(x, y, (rank, rank), |x, y| {
+            self.u.dyad(&self.v.monad(x)?, &self.v.monad(y)?)
+        })
+    }
+
+    fn monad_rank(&self) -> Rank {
+        if self.whole {
+            Rank::Infinite
+        } else {
+            self.v.monad_rank()
+        }
+    }
+
+    /// Undo u, and then v.
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        Ok(Rc::new(Compose {
+            u: self.v.inverse()?,
+            v: self.u.inverse()?,
+            whole: self.whole,
+        }))
+    }
+}
+
+/// `u&.v`: u under v: apply v, then u, then the inverse of v.
+fn under(u: &Word, v: &Word) -> Result<Word> {
+    match (u, v) {
+        (Word::Verb(u), Word::Verb(v)) => Ok(Word::Verb(Rc::new(Under {
+            u: u.clone(),
+            v: v.clone(),
+        }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u&.v`.
+#[derive(Debug)]
+struct Under {
+    u: Rc<dyn Verb>,
+    v: Rc<dyn Verb>,
+}
+
+impl Verb for Under {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}&.{}", self.u.display(), self.v.display()).into()
+    }
+
+    /// `u&.v y`: the inverse of v applied to `u v y`, on cells of the rank of
+    /// v.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let inverse = self.v.inverse()?;
+        monad_at_rank(y, self.v.monad_rank(), |y| {
+            inverse.monad(&self.u.monad(&self.v.monad(y)?)?)
+        })
+    }
+
+    /// `x u&.v y`: the inverse of v applied to `(v x) u (v y)`, on cells of
+    /// the rank of v.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let inverse = self.v.inverse()?;
+        let rank = self.v.monad_rank();
+        dyad_at_rank(x, y, (rank, rank), |x, y| {
+            inverse.monad(&self.u.dyad(&self.v.monad(x)?, &self.v.monad(y)?)?)
+        })
+    }
+
+    fn monad_rank(&self) -> Rank {
+        self.v.monad_rank()
+    }
+
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        Ok(Rc::new(Under {
+            u: self.u.inverse()?,
+            v: self.v.clone(),
+        }))
+    }
+}
+
+/// `u^:n`: power: apply u n times, or if n is negative, apply the inverse of
+/// u -n times; or if n is `_`, apply u until the result stops changing.
+///
+/// If n is a list of integers, the derived verb gives the result of applying
+/// u each of those numbers of times.
+fn power(u: &Word, n: &Word) -> Result<Word> {
+    let u = match u {
+        Word::Verb(u) => u.clone(),
+        _ => return Err(Error::Domain),
+    };
+    match n {
+        Word::Noun(Noun::Atom(a)) => match a.try_to_f64() {
+            Some(n) if n == f64::INFINITY => Ok(Word::Verb(Rc::new(Converge { u }))),
+            Some(_) => Ok(Word::Verb(Rc::new(Power {
+                u,
+                n: power_count(a)?,
+            }))),
+            None => Err(Error::Domain),
+        },
+        Word::Noun(Noun::Array(array)) => {
+            let powers = array
+                .iter_atoms()
+                .map(|a| {
+                    Ok(Power {
+                        u: u.clone(),
+                        n: power_count(a)?,
+                    })
+                })
+                .collect::<Result<Vec<Power>>>()?;
+            Ok(Word::Verb(Rc::new(Powers {
+                u,
+                n: array.clone(),
+                powers,
+            })))
+        }
+        Word::Verb(_) => Err(Error::Unimplemented("u^:v with a verb".into())),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// Convert one atom of the right argument of `^:` to a number of times to
+/// apply the verb.
+fn power_count(a: &Atom) -> Result<i64> {
+    match a.try_to_f64() {
+        Some(n) if n == f64::NEG_INFINITY => {
+            Err(Error::Unimplemented("u^:__ converge inverse".into()))
+        }
+        Some(n) if n.is_infinite() => Err(Error::Unimplemented(
+            "u^:_ converge in a list of powers".into(),
+        )),
+        Some(n) if n.fract() == 0.0 => Ok(n as i64),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u^:n`.
+#[derive(Debug)]
+struct Power {
+    u: Rc<dyn Verb>,
+    n: i64,
+}
+
+impl Power {
+    /// Apply `f` to y n times, or the inverse of u to y -n times.
+    ///
+    /// Returns [Error::Limit] if that's more than [limit::max_iterations]
+    /// times.
+    fn repeat<F>(&self, y: &Noun, f: F) -> Result<Noun>
+    where
+        F: Fn(&dyn Verb, &Noun) -> Result<Noun>,
+    {
+        if self.n.unsigned_abs() > limit::max_iterations() as u64 {
+            return Err(Error::Limit);
+        }
+        let inverse;
+        let verb: &dyn Verb = if self.n < 0 {
+            inverse = self.u.inverse()?;
+            inverse.as_ref()
+        } else {
+            self.u.as_ref()
+        };
+        let mut y = y.clone();
+        for _ in 0..self.n.unsigned_abs() {
+            y = f(verb, &y)?;
+        }
+        Ok(y)
+    }
+}
+
+impl Verb for Power {
+    fn display(&self) -> Cow<'_, str> {
+        let n = if self.n < 0 {
+            format!("_{}", self.n.unsigned_abs())
+        } else {
+            self.n.to_string()
+        };
+        format!("{}^:{}", self.u.display(), n).into()
+    }
+
+    /// `u^:n y`: apply u to y n times, so that `u^:0 y` is y.
+    ///
+    /// Returns [Error::Domain] if n is negative and u has no known inverse.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.repeat(y, |u, y| u.monad(y))
+    }
+
+    /// `x u^:n y`: apply u n times with x as the left argument and the
+    /// previous result as the right.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        if self.n < 0 {
+            return Err(Error::Unimplemented("inverse of x u^:n y".into()));
+        }
+        self.repeat(y, |u, y| u.dyad(x, y))
+    }
+
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        Ok(Rc::new(Power {
+            u: self.u.clone(),
+            n: -self.n,
+        }))
+    }
+}
+
+/// A verb derived from `u^:n` where n is a list of powers.
+#[derive(Debug)]
+struct Powers {
+    u: Rc<dyn Verb>,
+    /// The powers, as given, to show the verb.
+    n: Array,
+    /// The verb to apply for each atom of n.
+    powers: Vec<Power>,
+}
+
+impl Powers {
+    /// Apply `f` to each power, and assemble the results with the shape of
+    /// the list of powers as the frame.
+    fn each<F>(&self, f: F) -> Result<Noun>
+    where
+        F: Fn(&Power) -> Result<Noun>,
+    {
+        let results = self.powers.iter().map(f).collect::<Result<Vec<Noun>>>()?;
+        Noun::assemble(self.n.dims(), &results)
+    }
+}
+
+impl Verb for Powers {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}^:({})", self.u.display(), self.n).into()
+    }
+
+    /// `u^:n y`: the result of applying u to y each of n times.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.each(|power| power.monad(y))
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        self.each(|power| power.dyad(x, y))
+    }
+}
+
+/// A verb derived from `u^:_`.
+#[derive(Debug)]
+struct Converge {
+    u: Rc<dyn Verb>,
+}
+
+impl Converge {
+    /// Apply `f` to y until the result is the same as the previous one.
+    ///
+    /// Returns [Error::Limit] if the result is still changing after
+    /// [limit::max_iterations] applications.
+    fn converge<F>(&self, y: &Noun, f: F) -> Result<Noun>
+    where
+        F: Fn(&dyn Verb, &Noun) -> Result<Noun>,
+    {
+        let mut y = y.clone();
+        for _ in 0..limit::max_iterations() {
+            let next = f(self.u.as_ref(), &y)?;
+            if next == y {
+                return Ok(next);
+            }
+            y = next;
+        }
+        Err(Error::Limit)
+    }
+}
+
+impl Verb for Converge {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}^:_", self.u.display()).into()
+    }
+
+    /// `u^:_ y`: apply u to y until the result stops changing.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.converge(y, |u, y| u.monad(y))
+    }
+
+    /// `x u^:_ y`: apply u with x as the left argument and the previous
+    /// result as the right, until the result stops changing.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        self.converge(y, |u, y| u.dyad(x, y))
+    }
+}
+
+/// `u . v`: the inner product, such as matrix product `+/ . *`.
+fn dot(u: &Word, v: &Word) -> Result<Word> {
+    match (u, v) {
+        (Word::Verb(u), Word::Verb(v)) => Ok(Word::Verb(Rc::new(Dot {
+            u: u.clone(),
+            v: v.clone(),
+        }))),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// A verb derived from `u . v`.
+#[derive(Debug)]
+struct Dot {
+    u: Rc<dyn Verb>,
+    v: Rc<dyn Verb>,
+}
+
+impl Verb for Dot {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{} . {}", self.u.display(), self.v.display()).into()
+    }
+
+    fn monad(&self, _y: &Noun) -> Result<Noun> {
+        Err(Error::Unimplemented("determinant u . v y".into()))
+    }
+
+    /// `x u . v y`: apply v between each row of x and the whole of y, and
+    /// then u to the result, so that `+/ . *` is the matrix product.
+    ///
+    /// This is `u@(v"(1,_))`, which is J's definition when v has rank 0, as
+    /// it does in the usual cases. The general definition, which uses one
+    /// more than the left rank of v, is not implemented yet.
+    ///
+    /// Returns [Error::Length] if the last axis of x is not the same length as
+    /// the first axis of y.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        if let (Some(xn), Some(yn)) = (x.dims().last(), y.dims().first()) {
+            if xn != yn {
+                return Err(Error::Length);
+            }
+        }
+        dyad_at_rank(x, y, (Rank::Finite(1), Rank::Infinite), |x, y| {
+            self.u.monad(&self.v.dyad(x, y)?)
+        })
+    }
+}
+
+/// `u;.n`: cut: apply u to pieces of the argument separated by frets.
+///
+/// n is 1 or _1 for pieces that start at each fret, or 2 or _2 for pieces
+/// that end at each fret. When n is positive, the pieces include the fret
+/// item; when negative, it's dropped.
+fn cut(u: &Word, n: &Word) -> Result<Word> {
+    let u = match u {
+        Word::Verb(u) => u.clone(),
+        _ => return Err(Error::Domain),
+    };
+    let n = match n {
+        Word::Noun(Noun::Atom(a)) => a.try_to_f64().ok_or(Error::Domain)?,
+        Word::Noun(Noun::Array(_)) => return Err(Error::Rank),
+        _ => return Err(Error::Domain),
+    };
+    if ![1.0, -1.0, 2.0, -2.0].contains(&n) {
+        return Err(Error::Unimplemented(format!("cut with n = {}", n).into()));
+    }
+    Ok(Word::Verb(Rc::new(Cut {
+        u,
+        fret_at_end: n.abs() == 2.0,
+        keep_fret: n > 0.0,
+    })))
+}
+
+/// A verb derived from `u;.n`.
+#[derive(Debug)]
+struct Cut {
+    u: Rc<dyn Verb>,
+    /// True if each fret ends a piece, rather than starting one.
+    fret_at_end: bool,
+    /// True if the fret items are included in the pieces.
+    keep_fret: bool,
+}
+
+impl Cut {
+    /// Apply u to each piece of `items`, where `frets` marks the items that are
+    /// frets.
+    ///
+    /// Items before the first fret (or after the last, if frets end pieces) are
+    /// not in any piece.
+    fn apply(&self, frets: &[bool], items: &[Noun]) -> Result<Noun> {
+        let mut pieces: Vec<&[Noun]> = Vec::new();
+        let fret_indexes = frets.iter().enumerate().filter(|(_, &f)| f).map(|(i, _)| i);
+        if self.fret_at_end {
+            let mut start = 0;
+            for end in fret_indexes {
+                let piece = &items[start..=end];
+                pieces.push(if self.keep_fret {
+                    piece
+                } else {
+                    &piece[..piece.len() - 1]
+                });
+                start = end + 1;
+            }
+        } else {
+            let starts: Vec<usize> = fret_indexes.collect();
+            for (i, &start) in starts.iter().enumerate() {
+                let end = starts.get(i + 1).copied().unwrap_or(items.len());
+                let piece = &items[start..end];
+                pieces.push(if self.keep_fret { piece } else { &piece[1..] });
+            }
+        }
+        let results = pieces
+            .into_iter()
+            .map(|piece| self.u.monad(&Noun::assemble(&[piece.len()], piece)?))
+            .collect::<Result<Vec<Noun>>>()?;
+        Noun::assemble(&[results.len()], &results)
+    }
+
+    fn n(&self) -> i32 {
+        let n = if self.fret_at_end { 2 } else { 1 };
+        if self.keep_fret {
+            n
+        } else {
+            -n
+        }
+    }
+}
+
+impl Verb for Cut {
+    fn display(&self) -> Cow<'_, str> {
+        let n = self.n();
+        let n = if n < 0 {
+            format!("_{}", -n)
+        } else {
+            n.to_string()
+        };
+        format!("{};.{}", self.u.display(), n).into()
+    }
+
+    /// `u;.n y`: cut y using its first item (for 1 or _1) or its last item
+    /// (for 2 or _2) as the fret: for example `<;._2` splits a string that
+    /// ends with a delimiter.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        if y.dims().is_empty() {
+            return Err(Error::Rank);
+        }
+        let items = y.items();
+        let fret = if self.fret_at_end {
+            items.last()
+        } else {
+            items.first()
+        };
+        let frets: Vec<bool> = match fret {
+            Some(fret) => items.iter().map(|item| item == fret).collect(),
+            None => Vec::new(),
+        };
+        self.apply(&frets, &items)
+    }
+
+    /// `x u;.n y`: cut y at the items that correspond to 1s in the boolean
+    /// list x.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        if x.dims().len() != 1 || y.dims().is_empty() {
+            return Err(Error::Rank);
+        }
+        let items = y.items();
+        let frets = x
+            .iter_atoms()
+            .map(|a| match a.try_to_f64() {
+                Some(f) if f == 0.0 || f == 1.0 => Ok(f == 1.0),
+                _ => Err(Error::Domain),
+            })
+            .collect::<Result<Vec<bool>>>()?;
+        if frets.len() != items.len() {
+            return Err(Error::Length);
+        }
+        self.apply(&frets, &items)
+    }
+}
+
+/// `u!.f`: fit: a variant of u that uses f as its fill atom.
+///
+/// So far only `{!.f` is implemented, which selects an item of fills for an
+/// index that's out of range, rather than failing.
+fn fit(u: &Word, f: &Word) -> Result<Word> {
+    let u = match u {
+        Word::Verb(u) => u.clone(),
+        _ => return Err(Error::Domain),
+    };
+    let fill = match f {
+        Word::Noun(Noun::Atom(a)) => a.clone(),
+        Word::Noun(Noun::Array(_)) => return Err(Error::Rank),
+        _ => return Err(Error::Domain),
+    };
+    if !is_from(u.as_ref()) {
+        return Err(Error::Unimplemented(
+            format!("{}!.f fit", u.display()).into(),
+        ));
+    }
+    Ok(Word::Verb(Rc::new(Fit { u, fill })))
+}
+
+/// True if u is the primitive `{`, which is the only verb that knows how to
+/// use a fill, rather than some other verb that's named or shown as `{`.
+fn is_from(u: &dyn Verb) -> bool {
+    u.as_primitive().is_some_and(|p| p.name() == "{")
+}
+
+/// A verb derived from `u!.f`, where u is the primitive `{`.
+#[derive(Debug)]
+struct Fit {
+    /// The primitive `{`.
+    u: Rc<dyn Verb>,
+    fill: Atom,
+}
+
+impl Verb for Fit {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{}!.{}", self.u.display(), Noun::from(self.fill.clone())).into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.u.monad(y)
+    }
+
+    /// `x {!.f y`: u, selecting items made of the fill for indexes that are
+    /// out of range.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        debug_assert!(is_from(self.u.as_ref()));
+        from_with_fill(x, y, Some(&self.fill))
+    }
+}
@@ -13,15 +13,97 @@ pub enum Error {
     /// J language feature that's not supported yet.
     Unimplemented(Cow<'static, str>),
     IoError(std::io::Error),
-    /// The arrays are not the same shape or length.
+    /// A verb was applied as a monad or dyad but is only defined for the
+    /// other, such as an explicit definition that refers to `x` applied to
+    /// only one argument.
+    Valence,
+    /// The arrays have the same number of axes but their lengths don't agree.
     Length,
+    /// The arrays have a different number of axes and can't be matched up, or
+    /// an argument has too many axes for the operation.
+    Rank,
     /// The operation would use too much memory.
     ///
     /// (Because of memory overcommit on Linux etc, we're not exactly
     /// "out", but it would be imprudent to continue.)
     OutOfMemory,
+    /// A number is too large to be represented exactly as an integer.
+    Precision,
+    /// Sentences are nested too deeply within other sentences, probably by
+    /// unbounded recursion.
+    Stack,
+    /// A verb was applied repeatedly more times than allowed, such as by `^:_`
+    /// on a verb whose results never stop changing.
+    Limit,
     /// The expression is not interpretable in J's grammar as implemented.
     SyntaxError,
+    /// A sentence has an open paren without a matching close paren, or
+    /// vice versa.
+    UnbalancedParens,
+    /// A quoted string is not terminated.
+    OpenQuote,
+    /// An explicit definition started by `{{` is not closed by `}}`.
+    OpenDefinition,
+}
+
+/// The general kind of an [Error], for programs that need to handle some
+/// errors differently from others.
+///
+/// The kinds are stable: an error keeps its kind as new variants are added to
+/// [Error], and new kinds may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An argument is not in the domain of a verb, such as a character given
+    /// to an arithmetic verb.
+    Domain,
+    /// The lengths of arguments don't agree.
+    Length,
+    /// The numbers of axes of arguments don't agree, or there are too many.
+    Rank,
+    /// A sentence that scanned into words can't be evaluated.
+    Syntax,
+    /// A feature of J that's not supported yet.
+    Unimplemented,
+    /// Reading or writing a file failed.
+    Io,
+    /// The result would be too large.
+    OutOfMemory,
+    /// The text of a sentence can't be scanned into words, such as a bad
+    /// number or an unclosed quote.
+    Parse,
+    /// A number is too large to be represented exactly as an integer.
+    Precision,
+    /// Sentences are nested too deeply.
+    Stack,
+    /// A verb was applied repeatedly too many times.
+    Limit,
+    /// A verb was applied with the wrong number of arguments.
+    Valence,
+}
+
+impl Error {
+    /// The general kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Unexpected(_)
+            | Error::ParseNumber(_)
+            | Error::UnbalancedParens
+            | Error::OpenQuote
+            | Error::OpenDefinition => ErrorKind::Parse,
+            Error::Domain => ErrorKind::Domain,
+            Error::Unimplemented(_) => ErrorKind::Unimplemented,
+            Error::IoError(_) => ErrorKind::Io,
+            Error::Length => ErrorKind::Length,
+            Error::Rank => ErrorKind::Rank,
+            Error::OutOfMemory => ErrorKind::OutOfMemory,
+            Error::Precision => ErrorKind::Precision,
+            Error::Stack => ErrorKind::Stack,
+            Error::Limit => ErrorKind::Limit,
+            Error::Valence => ErrorKind::Valence,
+            Error::SyntaxError => ErrorKind::Syntax,
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -2,92 +2,707 @@
 
 //! Evaluate sentences.
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::atom::Atom;
 use crate::error::{Error, Result};
-use crate::scan::scan_sentence;
+use crate::flat::FlatResult;
+use crate::formatter::{NounFormatter, TextFormatter};
+use crate::limit::{self, Limits};
+use crate::noun::Noun;
+use crate::random::{self, Random};
+use crate::scan::{scan_sentence_with_names, Names};
+use crate::train::{Fork, Hook, Tine};
 use crate::verb::Verb;
 use crate::word::{Sentence, Word};
 
 /// A J interpreter session.
-#[derive(Debug, Default)]
-pub struct Session {}
+#[derive(Debug)]
+pub struct Session {
+    /// When rerunning a transcript, keep the existing output for sentences
+    /// that use unimplemented features.
+    keep_unimplemented: bool,
+    /// Verbs defined by the program embedding the interpreter, and other
+    /// names.
+    names: Names,
+    /// Log each verb application to stderr.
+    trace: bool,
+    /// Keep a log of each verb application, to be collected by
+    /// [Session::take_trace].
+    record_trace: bool,
+    /// Shared with nested sessions, so that they add to the same log.
+    trace_log: Rc<RefCell<Vec<String>>>,
+    /// Turns nouns that are the results of sentences into text.
+    formatter: Rc<dyn NounFormatter>,
+    /// Fail if a verb gives an integer too large to be exact.
+    check_precision: bool,
+    /// Limits on how long and how deeply sentences are evaluated.
+    limits: Limits,
+    /// Random numbers for `?`.
+    random: Rc<Random>,
+}
+
+/// The options that a session nested within a sentence, to evaluate `".` or
+/// an explicit definition, takes from the session evaluating the sentence.
+#[derive(Debug, Clone)]
+struct Inherited {
+    trace: bool,
+    record_trace: bool,
+    trace_log: Rc<RefCell<Vec<String>>>,
+    check_precision: bool,
+}
+
+thread_local! {
+    /// The options of the session that's evaluating a sentence on this
+    /// thread, if any.
+    static INHERITED: RefCell<Option<Inherited>> = const { RefCell::new(None) };
+}
+
+/// Puts back the options that were inherited before a sentence was
+/// evaluated, when dropped.
+struct RestoreInherited(Option<Inherited>);
+
+impl Drop for RestoreInherited {
+    fn drop(&mut self) {
+        INHERITED.with(|i| *i.borrow_mut() = self.0.take());
+    }
+}
 
 // TODO: Make this a configurable instance variable in the Session.
 const OUTPUT_WIDTH: usize = 80;
 
+/// An entry on the parser stack: either a word, or the mark at the left
+/// end of the sentence.
+#[derive(Debug)]
+enum Item {
+    Mark,
+    Word(Word),
+}
+
+impl Item {
+    /// True for items that can precede an expression that is complete on its
+    /// left: the start of the sentence, a copula, or an open paren.
+    fn is_edge(&self) -> bool {
+        matches!(
+            self,
+            Item::Mark | Item::Word(Word::Copula | Word::OpenParen)
+        )
+    }
+
+    /// True for edges, or any word that can be to the left of a verb
+    /// application: an adverb, verb, or noun.
+    fn is_edge_avn(&self) -> bool {
+        self.is_edge()
+            || matches!(
+                self,
+                Item::Word(Word::Adverb(_) | Word::Verb(_) | Word::Noun(_))
+            )
+    }
+
+    fn is_adverb(&self) -> bool {
+        matches!(self, Item::Word(Word::Adverb(_)))
+    }
+
+    fn is_verb(&self) -> bool {
+        matches!(self, Item::Word(Word::Verb(_)))
+    }
+
+    fn is_conjunction(&self) -> bool {
+        matches!(self, Item::Word(Word::Conjunction(_)))
+    }
+
+    fn is_name(&self) -> bool {
+        matches!(self, Item::Word(Word::Name(_)))
+    }
+
+    fn is_copula(&self) -> bool {
+        matches!(self, Item::Word(Word::Copula))
+    }
+
+    fn is_noun(&self) -> bool {
+        matches!(self, Item::Word(Word::Noun(_)))
+    }
+
+    fn is_verb_or_noun(&self) -> bool {
+        self.is_verb() || self.is_noun()
+    }
+
+    /// True for any word that can be the value of an expression.
+    fn is_value(&self) -> bool {
+        self.is_verb() || self.is_noun() || matches!(self, Item::Word(Word::Gerund(_)))
+    }
+
+    fn is_word(&self, w: &Word) -> bool {
+        matches!(self, Item::Word(a) if a == w)
+    }
+}
+
+impl Default for Session {
+    fn default() -> Session {
+        Session {
+            keep_unimplemented: false,
+            names: Names::default(),
+            trace: false,
+            record_trace: false,
+            trace_log: Rc::default(),
+            formatter: Rc::new(TextFormatter::default()),
+            check_precision: false,
+            limits: Limits::default(),
+            random: Rc::default(),
+        }
+    }
+}
+
 impl Session {
     pub fn new() -> Session {
-        Session {}
+        Session::default()
+    }
+
+    /// Make a session in which sentences can use the given names, with the
+    /// limits, random numbers, and tracing and precision options of the
+    /// session that's evaluating the current sentence.
+    pub(crate) fn with_names(names: Names) -> Session {
+        let mut session = Session {
+            names,
+            limits: limit::current(),
+            random: random::current(),
+            ..Session::default()
+        };
+        if let Some(inherited) = INHERITED.with(|i| i.borrow().clone()) {
+            session.trace = inherited.trace;
+            session.record_trace = inherited.record_trace;
+            session.trace_log = inherited.trace_log;
+            session.check_precision = inherited.check_precision;
+        }
+        session
+    }
+
+    /// Set whether rerunning a transcript should keep the existing output of
+    /// sentences that fail with [Error::Unimplemented], rather than replacing
+    /// it with the error.
+    ///
+    /// This is useful while writing documents that show features that are
+    /// not implemented yet.
+    pub fn set_keep_unimplemented(&mut self, keep_unimplemented: bool) {
+        self.keep_unimplemented = keep_unimplemented;
+    }
+
+    pub fn keep_unimplemented(&self) -> bool {
+        self.keep_unimplemented
+    }
+
+    /// Set whether to log each verb application, with its arguments and
+    /// result, to stderr.
+    ///
+    /// This shows the order in which a sentence is evaluated, which can help
+    /// in understanding tacit definitions.
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+
+    /// Set whether to keep a log of each verb application, with its
+    /// arguments and result, which can be collected by [Session::take_trace].
+    pub fn set_record_trace(&mut self, record_trace: bool) {
+        self.record_trace = record_trace;
+    }
+
+    /// Return the verb applications logged since this was last called, if
+    /// [Session::set_record_trace] is on, and clear the log.
+    pub fn take_trace(&self) -> Vec<String> {
+        self.trace_log.take()
+    }
+
+    /// Log one step of evaluation, if tracing or recording the trace.
+    fn trace<F>(&self, step: F)
+    where
+        F: FnOnce() -> String,
+    {
+        if self.trace || self.record_trace {
+            let step = step();
+            if self.trace {
+                eprintln!("trace: {}", step);
+            }
+            if self.record_trace {
+                self.trace_log.borrow_mut().push(step);
+            }
+        }
+    }
+
+    /// Define a verb that can be used by name in sentences evaluated in this
+    /// session.
+    ///
+    /// The name may be a primitive, such as `+`, in which case the new verb
+    /// is used instead of the primitive. Other names start with a letter,
+    /// followed by letters, digits, and underscores, such as `my_verb`, and
+    /// can also have any number of `.` or `:` inflections, such as `rand.`.
+    pub fn define_verb<S>(&mut self, name: S, verb: Rc<dyn Verb>)
+    where
+        S: Into<Vec<u8>>,
+    {
+        self.names.insert(name.into(), Word::Verb(verb));
+    }
+
+    /// The names defined in this session, by [Session::define_verb] or by
+    /// assignment, in sorted order.
+    pub fn defined_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .names
+            .keys()
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Set whether it's an error for a verb to give a result containing an
+    /// integer too large to be represented exactly, beyond 2^53, or for
+    /// `+`, `-`, or `*` to round an integer result to 2^53.
+    ///
+    /// Numbers are stored as floating point, so larger integers are silently
+    /// rounded, for example in `2 ^ 60`, which is usually fine for a
+    /// calculation with floats but may be surprising if integers were
+    /// expected. With this check on, such a verb fails with
+    /// [Error::Precision]. Since floats and integers are not distinguished,
+    /// this applies to any large number, such as `1e300 + 1`, but not to
+    /// infinities.
+    ///
+    /// Only the result of each verb in the sentence is checked, not values
+    /// computed inside a verb, such as within `+/`.
+    pub fn set_check_precision(&mut self, check_precision: bool) {
+        self.check_precision = check_precision;
+    }
+
+    /// Set whether boxes are drawn with ASCII characters like `+-+`, rather
+    /// than Unicode box-drawing characters like `┌─┐`.
+    ///
+    /// ASCII boxes are more portable, and are used by default in Markdown
+    /// documents.
+    ///
+    /// This replaces any formatter set by [Session::set_formatter] with a
+    /// [TextFormatter].
+    pub fn set_ascii_boxes(&mut self, ascii_boxes: bool) {
+        self.formatter = Rc::new(TextFormatter { ascii_boxes });
+    }
+
+    /// Restart the random numbers used by `?` from a seed, so that they're
+    /// the same each time.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random.seed(seed);
+    }
+
+    /// Set the most times `u^:_` applies u while waiting for the result to
+    /// stop changing, before giving [Error::Limit]. This is also the largest
+    /// power n for which `u^:n` applies u.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.limits.max_iterations = max_iterations;
+    }
+
+    /// Set how deeply sentences can be evaluated within other sentences, by
+    /// explicit definitions or `".`, before giving [Error::Stack].
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.limits.max_depth = max_depth;
+    }
+
+    /// Set how nouns that are the results of sentences are turned into text,
+    /// by [Session::eval_text] and [Session::format_result].
+    ///
+    /// By default they're shown as plain text by a [TextFormatter].
+    pub fn set_formatter(&mut self, formatter: Rc<dyn NounFormatter>) {
+        self.formatter = formatter;
     }
 
     /// Evaluate one line (as text) and return the result (as text).
     pub fn eval_text(&mut self, line: &str) -> String {
-        match scan_sentence(line).and_then(|s| self.eval_sentence(&s)) {
-            Ok(Some(word)) => format!("{:.*}", OUTPUT_WIDTH, word),
+        let result = self.eval_line(line);
+        self.format_result(result)
+    }
+
+    /// Check the result of a verb according to the session's options.
+    ///
+    /// With the precision check on, a result beyond 2^53 is an error, since
+    /// it may have been rounded.
+    fn check_result(&self, result: Noun) -> Result<Noun> {
+        if self.check_precision && result.iter_atoms().any(Atom::is_inexact_integer) {
+            Err(Error::Precision)
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Check the result of a dyad according to the session's options.
+    ///
+    /// As well as the checks of [Session::check_result], this catches
+    /// arithmetic on integers whose exact result was rounded to exactly 2^53,
+    /// such as `9007199254740991 + 2`.
+    fn check_dyad_result(&self, verb: &dyn Verb, x: &Noun, y: &Noun, r: Noun) -> Result<Noun> {
+        if self.check_precision
+            && verb
+                .as_primitive()
+                .is_some_and(|p| p.dyad_rounds_integers(x, y))
+        {
+            return Err(Error::Precision);
+        }
+        self.check_result(r)
+    }
+
+    /// Format the result of evaluating a sentence as text, as it's shown to
+    /// the user.
+    ///
+    /// Nouns are formatted by the session's [NounFormatter].
+    pub fn format_result(&self, result: Result<Option<Word>>) -> String {
+        match result {
+            Ok(Some(Word::Noun(noun))) => self.formatter.format(&noun, OUTPUT_WIDTH),
+            Ok(Some(word)) => word.to_string(),
             Ok(None) => String::new(),
             Err(err) => format!("error: {:?}", err),
         }
     }
 
+    /// Scan and evaluate one line, returning the resulting word, if any.
+    ///
+    /// As in J, a sentence that starts by assigning a name, such as
+    /// `a =: 1 + 2`, gives no result to show.
+    pub fn eval_line(&mut self, line: &str) -> Result<Option<Word>> {
+        let sentence = scan_sentence_with_names(line, &self.names)?;
+        let result = self.eval_sentence(&sentence)?;
+        if is_assignment(&sentence) {
+            Ok(None)
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Scan and evaluate one line, and return the resulting noun as a
+    /// [FlatResult], which is easier to pass to other languages.
+    ///
+    /// Returns [Error::Domain] if the line doesn't evaluate to a noun.
+    pub fn eval_to_flat(&mut self, line: &str) -> Result<FlatResult> {
+        match self.eval_line(line)? {
+            Some(Word::Noun(noun)) => FlatResult::try_from(&noun),
+            _ => Err(Error::Domain),
+        }
+    }
+
+    /// Evaluate each line of a block of text in order, and return the result
+    /// of each.
+    ///
+    /// Lines that are empty or contain only a comment are skipped, and give no
+    /// result. Lines that evaluate to something other than a noun, such as a
+    /// verb, and lines that assign a name, give `None`.
+    ///
+    /// As in J, an error in one line doesn't stop the following lines from
+    /// being evaluated, and doesn't undo any names defined in the session.
+    ///
+    /// If the first line starts with `#!`, it's skipped, so that the text can
+    /// be an executable script such as one starting `#!/usr/bin/env rsj`.
+    pub fn eval_all(&mut self, text: &str) -> Vec<Result<Option<Noun>>> {
+        let mut results = Vec::new();
+        let mut lines = text.lines().peekable();
+        lines.next_if(|line| line.starts_with("#!"));
+        for line in lines {
+            let sentence = match scan_sentence_with_names(line, &self.names) {
+                Ok(sentence) if sentence.is_empty() => continue,
+                Ok(sentence) => sentence,
+                Err(err) => {
+                    results.push(Err(err));
+                    continue;
+                }
+            };
+            results.push(self.eval_sentence(&sentence).map(|word| match word {
+                Some(Word::Noun(noun)) if !is_assignment(&sentence) => Some(noun),
+                _ => None,
+            }));
+        }
+        results
+    }
+
     /// Evaluate a parsed sentence and return the result.
     pub fn eval_sentence(&mut self, sentence: &Sentence) -> Result<Option<Word>> {
-        // Evaluation proceeds from right to left, looking for patterns that can be evaluated
-        // and reduced.
+        self.parse(sentence, |_rule, _queue, _stack| ())
+    }
+
+    /// Evaluate one line, and describe how J parses it: first its words, and
+    /// then the sentence as it stands after each reduction, with the name of
+    /// the rule that made it, such as `dyad` or `fork`.
+    ///
+    /// The sentence is evaluated with copies of the session's names and
+    /// random numbers, so explaining it doesn't assign names or change the
+    /// numbers that `?` gives next.
+    ///
+    /// If the sentence fails, the last line is the error.
+    pub fn explain_parse(&mut self, line: &str) -> Vec<String> {
+        let sentence = match scan_sentence_with_names(line, &self.names) {
+            Ok(sentence) => sentence,
+            Err(err) => return vec![format!("error: {:?}", err)],
+        };
+        let words: Vec<String> = sentence.iter().map(describe_word).collect();
+        let mut lines = vec![format!("words: {}", words.join(" | "))];
+        let names = self.names.clone();
+        let scratch_random = Rc::new(Random::clone(&self.random));
+        let random = std::mem::replace(&mut self.random, scratch_random);
+        let result = self.parse(&sentence, |rule, queue, stack| {
+            let words: Vec<String> = queue
+                .iter()
+                .chain(stack)
+                .filter_map(|item| match item {
+                    Item::Mark => None,
+                    Item::Word(w) => Some(describe_word(w)),
+                })
+                .collect();
+            lines.push(format!("{:<12}{}", rule, words.join(" | ")));
+        });
+        self.names = names;
+        self.random = random;
+        if let Err(err) = result {
+            lines.push(format!("error: {:?}", err));
+        }
+        lines
+    }
+
+    /// Evaluate a sentence, calling `step` with the name of the rule, the
+    /// words not yet moved, and the stack, after each reduction.
+    fn parse<F>(&mut self, sentence: &Sentence, step: F) -> Result<Option<Word>>
+    where
+        F: FnMut(&str, &[Item], &[Item]),
+    {
+        // Verbs applied while evaluating the sentence, including in nested
+        // sessions, see this session's options, limits, and random numbers.
+        let inherited = Inherited {
+            trace: self.trace,
+            record_trace: self.record_trace,
+            trace_log: self.trace_log.clone(),
+            check_precision: self.check_precision,
+        };
+        let _restore = RestoreInherited(INHERITED.with(|i| i.replace(Some(inherited))));
+        let random = self.random.clone();
+        limit::with_limits(self.limits, || {
+            random::with_random(&random, || self.reduce_sentence(sentence, step))
+        })
+    }
+
+    fn reduce_sentence<F>(&mut self, sentence: &Sentence, mut step: F) -> Result<Option<Word>>
+    where
+        F: FnMut(&str, &[Item], &[Item]),
+    {
+        // Words are moved one at a time from the right-hand end of the
+        // sentence onto the left of a stack. After each move, the first four
+        // items on the stack are compared to the patterns of J's grammar, and
+        // if one matches, those words are reduced. When nothing matches,
+        // another word is moved.
         //
         // See https://www.jsoftware.com/help/dictionary/dicte.htm.
-        let mut stack: Vec<Word> = sentence.clone();
-        // We're currently trying to evaluate stack[cursor..(cursor+4)].
-        let mut cursor = stack.len();
+        check_parens(sentence)?;
+        let mut queue: Vec<Item> = std::iter::once(Item::Mark)
+            .chain(sentence.iter().cloned().map(Item::Word))
+            .collect();
+        // The leftmost word is at stack[0].
+        let mut stack: Vec<Item> = Vec::new();
         loop {
-            // dbg!(&cursor, &stack);
-            // match START ^ VERB:v NOUN:y ...
-            // or VERB ^ VERB:v NOUN:y
-            // or OPENPAREN ^ VERB:v NOUN:y
-            // into applying v to y
-            if cursor == 0 || matches!(stack[cursor - 1], Word::Verb(..) | Word::OpenParen) {
-                // TODO: Assignment should also match here.
-                if let [Word::Verb(v), Word::Noun(y), ..] = &stack[cursor..] {
-                    stack[cursor] = Word::Noun(v.monad(y)?);
-                    stack.remove(cursor + 1);
-                }
+            if let Some(rule) = reduce(&mut stack, self)? {
+                step(rule, &queue, &stack);
+                continue;
             }
-            if let [Word::Noun(x), Word::Verb(v), Word::Noun(y), ..] = &stack[cursor..] {
-                // ... NOUN:x VERB:v NOUN:y ...
-                stack[cursor] = Word::Noun(v.dyad(x, y)?);
-                stack.remove(cursor + 1);
-                stack.remove(cursor + 1);
-            } else if let [Word::OpenParen, Word::Verb(_) | Word::Noun(_), Word::CloseParen, ..] =
-                &stack[cursor..]
-            {
-                // ... OPEN w CLOSE => w
-                stack.remove(cursor);
-                stack.remove(cursor + 1);
-                cursor += 1;
+            match queue.pop() {
+                Some(item) => stack.insert(0, item),
+                None => break,
             }
-            if cursor == 0 {
-                break;
-            } else {
-                cursor -= 1
-            };
         }
-        match stack.len() {
-            0 => Ok(None),
-            1 => {
-                let w = stack.pop().unwrap();
-                // TODO: This feels kludgey and indicates perhaps the word type
-                // should not contain both syntax like parens and also nouns and
-                // verbs?
-                if matches!(w, Word::Noun(_) | Word::Verb(_)) {
-                    Ok(Some(w))
-                } else {
-                    Err(Error::SyntaxError)
-                }
+        match stack.as_slice() {
+            [Item::Mark] => Ok(None),
+            [Item::Mark, Item::Word(w @ (Word::Noun(_) | Word::Verb(_) | Word::Gerund(_)))] => {
+                Ok(Some(w.clone()))
+            }
+            // If the stack wasn't reduced to a single word that's probably
+            // because it contains some grammar that's either invalid, or at
+            // least not implemented yet.
+            _ => Err(Error::SyntaxError),
+        }
+    }
+}
+
+/// Show a word on one line, as it could be written in a sentence: strings are
+/// quoted, and tables are shown as a reshaped list.
+fn describe_word(word: &Word) -> String {
+    let noun = match word {
+        Word::Noun(noun) => noun,
+        word => return word.to_string(),
+    };
+    let atoms = if noun.iter_atoms().next().is_some()
+        && noun.iter_atoms().all(|a| matches!(a, Atom::Char(_)))
+    {
+        let text: String = noun
+            .iter_atoms()
+            .map(|a| match a {
+                Atom::Char(c) => *c as char,
+                _ => unreachable!(),
+            })
+            .collect();
+        format!("'{}'", text.replace('\'', "''"))
+    } else {
+        let atoms = Noun::from(noun.iter_atoms().cloned().collect::<Vec<Atom>>());
+        format!("{:.*}", OUTPUT_WIDTH, atoms)
+    };
+    if noun.dims().len() > 1 {
+        let shape: Vec<String> = noun.dims().iter().map(usize::to_string).collect();
+        format!("({} $ {})", shape.join(" "), atoms)
+    } else {
+        atoms
+    }
+}
+
+/// True if the sentence starts by assigning a name.
+fn is_assignment(sentence: &Sentence) -> bool {
+    matches!(sentence.as_slice(), [Word::Name(_), Word::Copula, ..])
+}
+
+/// Check that every open paren in the sentence is matched by a later close
+/// paren, and vice versa.
+///
+/// Otherwise, the sentence can't be reduced to a single word, but this gives a
+/// more specific error than [Error::SyntaxError].
+fn check_parens(sentence: &Sentence) -> Result<()> {
+    let mut depth = 0usize;
+    for word in sentence {
+        match word {
+            Word::OpenParen => depth += 1,
+            Word::CloseParen => depth = depth.checked_sub(1).ok_or(Error::UnbalancedParens)?,
+            _ => (),
+        }
+    }
+    if depth == 0 {
+        Ok(())
+    } else {
+        Err(Error::UnbalancedParens)
+    }
+}
+
+/// Try to reduce the words at the start of the stack by matching them to one of
+/// J's parsing rules.
+///
+/// Verbs are applied according to the session's options for tracing and
+/// checking their results.
+///
+/// Returns the name of the rule, if a reduction was made.
+fn reduce(stack: &mut Vec<Item>, session: &mut Session) -> Result<Option<&'static str>> {
+    let any = Item::Mark; // A placeholder for positions beyond the stack.
+    let s = |i: usize| stack.get(i).unwrap_or(&any);
+    let rule = if s(0).is_name() && s(1).is_copula() && s(2).is_value() {
+        // Assignment: NAME:n COPULA VERB|NOUN:w => w, defining n as w
+        match (s(0), s(2)) {
+            (Item::Word(Word::Name(name)), Item::Word(w)) => {
+                session.names.insert(name.clone(), w.clone());
             }
-            _ => {
-                // If the stack wasn't reduced to a single word that's probably
-                // because it contains some grammar that's either invalid, or at least not
-                // implemented yet.
-                Err(Error::SyntaxError)
+            _ => unreachable!(),
+        }
+        stack.drain(0..2);
+        "assignment"
+    } else if s(0).is_edge() && s(1).is_verb() && s(2).is_noun() {
+        // Monad: EDGE VERB:v NOUN:y => v y
+        let y = apply_monad(s(1), s(2), session)?;
+        stack.splice(1..3, [y]);
+        "monad"
+    } else if s(0).is_edge_avn() && s(1).is_verb() && s(2).is_verb() && s(3).is_noun() {
+        // Monad: EDGE+AVN VERB VERB:v NOUN:y => v y
+        let y = apply_monad(s(2), s(3), session)?;
+        stack.splice(2..4, [y]);
+        "monad"
+    } else if s(0).is_edge_avn() && s(1).is_noun() && s(2).is_verb() && s(3).is_noun() {
+        // Dyad: EDGE+AVN NOUN:x VERB:v NOUN:y => x v y
+        let r = match (s(1), s(2), s(3)) {
+            (Item::Word(Word::Noun(x)), Item::Word(Word::Verb(v)), Item::Word(Word::Noun(y))) => {
+                let r = session.check_dyad_result(v.as_ref(), x, y, v.dyad(x, y)?)?;
+                session.trace(|| {
+                    format!(
+                        "dyad ({:.*}) {} ({:.*}) => {:.*}",
+                        OUTPUT_WIDTH,
+                        x,
+                        v.display(),
+                        OUTPUT_WIDTH,
+                        y,
+                        OUTPUT_WIDTH,
+                        r
+                    )
+                });
+                Word::Noun(r)
             }
+            _ => unreachable!(),
+        };
+        stack.splice(1..4, [Item::Word(r)]);
+        "dyad"
+    } else if s(0).is_edge_avn() && s(1).is_verb_or_noun() && s(2).is_adverb() {
+        // Adverb: EDGE+AVN VERB|NOUN:u ADV:a => u a
+        let r = match (s(1), s(2)) {
+            (Item::Word(u), Item::Word(Word::Adverb(a))) => a.apply(u)?,
+            _ => unreachable!(),
+        };
+        stack.splice(1..3, [Item::Word(r)]);
+        "adverb"
+    } else if s(0).is_edge_avn() && s(1).is_value() && s(2).is_conjunction() && s(3).is_value() {
+        // Conjunction: EDGE+AVN VERB|NOUN:u CONJ:c VERB|NOUN:v => u c v
+        let r = match (s(1), s(2), s(3)) {
+            (Item::Word(u), Item::Word(Word::Conjunction(c)), Item::Word(v)) => c.apply(u, v)?,
+            _ => unreachable!(),
+        };
+        stack.splice(1..4, [Item::Word(r)]);
+        "conjunction"
+    } else if s(0).is_edge_avn() && s(1).is_verb_or_noun() && s(2).is_verb() && s(3).is_verb() {
+        // Fork: EDGE+AVN VERB|NOUN VERB VERB => (f g h)
+        let fork = match (s(1), s(2), s(3)) {
+            (Item::Word(f), Item::Word(Word::Verb(g)), Item::Word(Word::Verb(h))) => Fork {
+                f: match f {
+                    Word::Verb(f) => Tine::Verb(f.clone()),
+                    Word::Noun(n) => Tine::Noun(n.clone()),
+                    _ => unreachable!(),
+                },
+                g: g.clone(),
+                h: h.clone(),
+            },
+            _ => unreachable!(),
+        };
+        stack.splice(1..4, [Item::Word(Word::Verb(Rc::new(fork)))]);
+        "fork"
+    } else if s(0).is_edge() && s(1).is_verb() && s(2).is_verb() {
+        // Hook: EDGE VERB VERB => (f g)
+        let hook = match (s(1), s(2)) {
+            (Item::Word(Word::Verb(f)), Item::Word(Word::Verb(g))) => Hook {
+                f: f.clone(),
+                g: g.clone(),
+            },
+            _ => unreachable!(),
+        };
+        stack.splice(1..3, [Item::Word(Word::Verb(Rc::new(hook)))]);
+        "hook"
+    } else if s(0).is_word(&Word::OpenParen) && s(1).is_value() && s(2).is_word(&Word::CloseParen) {
+        // Parens: ( w ) => w
+        stack.remove(2);
+        stack.remove(0);
+        "parens"
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(rule))
+}
+
+/// Apply a verb item to a noun item, returning the result as a new item.
+///
+/// If tracing is on in the session, the application is logged.
+fn apply_monad(verb: &Item, noun: &Item, session: &Session) -> Result<Item> {
+    match (verb, noun) {
+        (Item::Word(Word::Verb(v)), Item::Word(Word::Noun(y))) => {
+            let r = session.check_result(v.monad(y)?)?;
+            session.trace(|| {
+                format!(
+                    "monad {} ({:.*}) => {:.*}",
+                    v.display(),
+                    OUTPUT_WIDTH,
+                    y,
+                    OUTPUT_WIDTH,
+                    r
+                )
+            });
+            Ok(Item::Word(Word::Noun(r)))
         }
+        _ => unreachable!(),
     }
 }
@@ -0,0 +1,164 @@
+// Copyright 2022 Martin Pool
+
+//! Explicit definitions, such as `{{ y + 1 }}`, which define a verb by a
+//! sentence that refers to its argument by name; and `".`, which executes a
+//! sentence given as a string.
+//!
+//! See <https://code.jsoftware.com/wiki/Vocabulary/DirectDefinition>.
+
+use std::borrow::Cow;
+use std::cell::Cell;
+
+use crate::atom::Atom;
+use crate::error::{Error, Result};
+use crate::eval::Session;
+use crate::limit;
+use crate::noun::Noun;
+use crate::scan::{scan_sentence_with_names, word_texts, Names};
+use crate::verb::Verb;
+use crate::word::Word;
+
+/// A verb defined by the text of a sentence, in which `y` is the right
+/// argument.
+///
+/// As in J, a definition whose body refers to `x` is a dyad, with `x` as its
+/// left argument, and otherwise it's a monad.
+#[derive(Debug)]
+pub struct Explicit {
+    body: String,
+    /// The names that were defined where the verb was defined.
+    names: Names,
+    /// True if the body refers to `x`, so that the verb is a dyad rather
+    /// than a monad.
+    uses_x: bool,
+}
+
+impl Explicit {
+    pub fn new(body: String, names: Names) -> Explicit {
+        let uses_x = word_texts(&body).is_ok_and(|words| words.contains(&"x"));
+        Explicit {
+            body,
+            names,
+            uses_x,
+        }
+    }
+
+    /// Evaluate the body with the given arguments bound to names.
+    fn eval(&self, args: &[(&str, &Noun)]) -> Result<Noun> {
+        let mut names = self.names.clone();
+        for (name, value) in args {
+            names.insert(name.as_bytes().to_vec(), Word::Noun((*value).clone()));
+        }
+        // The body must give a noun.
+        eval_nested(&self.body, names)?.ok_or(Error::SyntaxError)
+    }
+}
+
+thread_local! {
+    /// How many sentences are being evaluated within other sentences.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Evaluate a sentence from within a verb, giving the resulting noun, if
+/// any.
+///
+/// Returns [Error::SyntaxError] if the sentence gives a verb or other word that's
+/// not a noun.
+fn eval_nested(text: &str, names: Names) -> Result<Option<Noun>> {
+    let depth = DEPTH.with(|d| d.get());
+    if depth >= limit::max_depth() {
+        return Err(Error::Stack);
+    }
+    DEPTH.with(|d| d.set(depth + 1));
+    let result = scan_sentence_with_names(text, &names)
+        .and_then(|sentence| Session::with_names(names).eval_sentence(&sentence));
+    DEPTH.with(|d| d.set(depth));
+    match result? {
+        Some(Word::Noun(noun)) => Ok(Some(noun)),
+        Some(_) => Err(Error::SyntaxError),
+        None => Ok(None),
+    }
+}
+
+impl Verb for Explicit {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{{{{ {} }}}}", self.body).into()
+    }
+
+    /// Returns [Error::Valence] if the body refers to `x`.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        if self.uses_x {
+            return Err(Error::Valence);
+        }
+        self.eval(&[("y", y)])
+    }
+
+    /// Returns [Error::Valence] unless the body refers to `x`.
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        if !self.uses_x {
+            return Err(Error::Valence);
+        }
+        self.eval(&[("x", x), ("y", y)])
+    }
+}
+
+/// `". y`: execute: evaluate the sentence in the string y.
+///
+/// Since the verb evaluates the sentence with the names that were defined
+/// where it was used, a new instance is made each time `".` is scanned.
+#[derive(Debug)]
+pub struct Execute {
+    names: Names,
+}
+
+impl Execute {
+    pub fn new(names: Names) -> Execute {
+        Execute { names }
+    }
+}
+
+impl Verb for Execute {
+    fn display(&self) -> Cow<'_, str> {
+        "\".".into()
+    }
+
+    /// A sentence that gives no result, such as an empty string or a
+    /// comment, gives an empty list.
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        if y.dims().len() > 1 {
+            return Err(Error::Unimplemented("execute a table of sentences".into()));
+        }
+        let text = y
+            .iter_atoms()
+            .map(|a| match a {
+                Atom::Char(c) => Ok(*c as char),
+                _ => Err(Error::Domain),
+            })
+            .collect::<Result<String>>()?;
+        Ok(eval_nested(&text, self.names.clone())?.unwrap_or_else(|| Noun::from(vec![])))
+    }
+
+    fn dyad(&self, _x: &Noun, _y: &Noun) -> Result<Noun> {
+        Err(Error::Unimplemented("numbers x \". y".into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn too_deep() {
+        assert_eq!(
+            eval_nested("1 + 2", Names::new()).unwrap(),
+            Some(Noun::from(3.0))
+        );
+        DEPTH.with(|d| d.set(limit::max_depth()));
+        assert!(matches!(
+            eval_nested("1 + 2", Names::new()),
+            Err(Error::Stack)
+        ));
+        assert_eq!(DEPTH.with(|d| d.get()), limit::max_depth());
+        DEPTH.with(|d| d.set(0));
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2022 Martin Pool
+
+//! A flat representation of nouns, for passing results across a foreign
+//! function interface.
+//!
+//! A [FlatResult] holds only a shape and lists of numbers, so that programs
+//! in other languages can read it without knowing about [Atom] or [Noun].
+
+use crate::atom::Atom;
+use crate::error::{Error, Result};
+use crate::noun::Noun;
+
+/// The type of the atoms in a [FlatResult].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatType {
+    /// Numbers, with their real and imaginary parts.
+    Number,
+    /// Characters, with the byte value of each character as its real part.
+    Char,
+}
+
+/// A noun as a shape and its atoms in row-major order.
+///
+/// There's one entry in `reals` and `imags` for each atom. An atom has an
+/// empty shape and one entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatResult {
+    /// The length of each axis.
+    pub shape: Vec<usize>,
+    /// The real part of each atom.
+    pub reals: Vec<f64>,
+    /// The imaginary part of each atom, which is 0 for real numbers and
+    /// characters.
+    pub imags: Vec<f64>,
+    pub atom_type: FlatType,
+}
+
+/// Flatten a noun.
+///
+/// An empty array has no atoms to show its type, so it's a number. Boxes can't
+/// be flattened, and give [Error::Unimplemented].
+impl TryFrom<&Noun> for FlatResult {
+    type Error = Error;
+
+    fn try_from(noun: &Noun) -> Result<FlatResult> {
+        let atom_type = match noun.iter_atoms().next() {
+            Some(Atom::Char(_)) => FlatType::Char,
+            _ => FlatType::Number,
+        };
+        let (reals, imags) = noun
+            .iter_atoms()
+            .map(|atom| match atom {
+                Atom::Complex(c) => Ok((c.re, c.im)),
+                Atom::Char(c) => Ok((*c as f64, 0.0)),
+                Atom::Boxed(_) => Err(Error::Unimplemented("flat result of boxes".into())),
+            })
+            .collect::<Result<Vec<(f64, f64)>>>()?
+            .into_iter()
+            .unzip();
+        Ok(FlatResult {
+            shape: noun.dims().to_vec(),
+            reals,
+            imags,
+            atom_type,
+        })
+    }
+}
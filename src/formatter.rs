@@ -0,0 +1,38 @@
+// Copyright 2022 Martin Pool
+
+//! Turn nouns into text to show to the user.
+//!
+//! A [Session](crate::eval::Session) formats its results through a
+//! [NounFormatter], so that programs embedding the interpreter can show nouns
+//! in other ways, such as HTML tables.
+
+use std::fmt;
+
+use crate::noun::Noun;
+
+/// Turns a noun into text.
+pub trait NounFormatter: fmt::Debug {
+    /// Format a noun, keeping lines within `width` characters where possible.
+    fn format(&self, noun: &Noun, width: usize) -> String;
+}
+
+/// Format nouns as plain text, in the way J shows them in a terminal.
+///
+/// This is the formatter used by default.
+#[derive(Debug, Default, Clone)]
+pub struct TextFormatter {
+    /// Draw boxes with ASCII characters rather than Unicode box-drawing
+    /// characters.
+    pub ascii_boxes: bool,
+}
+
+impl NounFormatter for TextFormatter {
+    fn format(&self, noun: &Noun, width: usize) -> String {
+        if self.ascii_boxes {
+            // The alternate flag selects ASCII boxes.
+            format!("{:#.*}", width, noun)
+        } else {
+            format!("{:.*}", width, noun)
+        }
+    }
+}
@@ -15,6 +15,11 @@ impl<'buf> Lex<'buf> {
         Lex { buf, pos: 0 }
     }
 
+    /// The position of the cursor, as a byte offset into the input.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
     /// True if at the end of the input.
     pub fn is_end(&self) -> bool {
         self.pos >= self.buf.len()
@@ -67,6 +72,23 @@ impl<'buf> Lex<'buf> {
         c
     }
 
+    /// Return the next `n` bytes without consuming them.
+    ///
+    /// Panics if there are fewer than `n` bytes remaining.
+    #[must_use]
+    pub fn peek_n(&self, n: usize) -> &'buf [u8] {
+        &self.buf[self.pos..self.pos + n]
+    }
+
+    /// Take and return the next `n` bytes.
+    ///
+    /// Panics if there are fewer than `n` bytes remaining.
+    pub fn take_n(&mut self, n: usize) -> &'buf [u8] {
+        let s = self.peek_n(n);
+        self.pos += n;
+        s
+    }
+
     /// Discard the current character.
     pub fn drop(&mut self) {
         self.advance();
@@ -112,13 +134,6 @@ impl<'buf> Lex<'buf> {
     /// Test if the next few characters match `s`.
     #[must_use]
     pub fn starts_with(&self, s: &[u8]) -> bool {
-        let mut p = self.pos;
-        for c in s {
-            if self.buf.get(p) != Some(c) {
-                return false;
-            }
-            p += 1;
-        }
-        true
+        self.buf[self.pos..].starts_with(s)
     }
 }
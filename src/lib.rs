@@ -2,16 +2,25 @@
 
 //! Toy implementation of J in Rust.
 
+pub mod adverb;
 pub mod array;
 pub mod atom;
+pub mod conjunction;
 pub mod error;
 pub mod eval;
+pub mod explicit;
+pub mod flat;
+pub mod formatter;
 pub mod lex;
+pub mod limit;
 pub mod markdown;
+pub mod ndjson;
 pub mod noun;
 pub mod primitive;
+pub mod random;
 pub mod repl;
 pub mod scan;
+pub mod train;
 pub mod transcript;
 pub mod verb;
 pub mod word;
@@ -0,0 +1,98 @@
+// Copyright 2022 Martin Pool
+
+//! Limits that stop evaluation from running or recursing without bound, such
+//! as `^:_` on a verb whose results never stop changing, or `".` evaluating a
+//! sentence that evaluates itself.
+//!
+//! Each session has its own limits, which can be changed through
+//! [Session::set_max_iterations](crate::eval::Session::set_max_iterations)
+//! and [Session::set_max_depth](crate::eval::Session::set_max_depth). While a
+//! session is evaluating a sentence its limits apply to the verbs it calls.
+
+use std::cell::Cell;
+
+/// The default for [Limits::max_iterations].
+pub const DEFAULT_MAX_ITERATIONS: usize = 100_000;
+
+/// The default for [Limits::max_depth].
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// The limits on evaluating sentences in one session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The most times `u^:_` applies u while waiting for the result to stop
+    /// changing, before giving [Error::Limit](crate::error::Error::Limit),
+    /// and the largest power n for which `u^:n` applies u.
+    pub max_iterations: usize,
+    /// The deepest that sentences can be evaluated within other sentences,
+    /// by explicit definitions or `".`, before giving
+    /// [Error::Stack](crate::error::Error::Stack).
+    pub max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+thread_local! {
+    /// The limits of the session that's evaluating a sentence on this thread.
+    static CURRENT: Cell<Limits> = const {
+        Cell::new(Limits {
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_depth: DEFAULT_MAX_DEPTH,
+        })
+    };
+}
+
+/// Call `f` with `limits` applying to the verbs it evaluates, and then go
+/// back to the previous limits, even if `f` panics.
+pub(crate) fn with_limits<R>(limits: Limits, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CURRENT.with(|c| c.replace(limits)));
+    f()
+}
+
+/// Puts back the limits that applied before [with_limits] when dropped.
+struct Restore(Limits);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|c| c.set(self.0));
+    }
+}
+
+/// The limits of the session that's evaluating a sentence, or the defaults
+/// outside of any session.
+pub(crate) fn current() -> Limits {
+    CURRENT.with(|c| c.get())
+}
+
+/// The most times `u^:_` or `u^:n` applies u.
+pub(crate) fn max_iterations() -> usize {
+    current().max_iterations
+}
+
+/// The deepest that sentences can be evaluated within other sentences.
+pub(crate) fn max_depth() -> usize {
+    current().max_depth
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn limits_are_restored_after_a_panic() {
+        let limits = Limits {
+            max_iterations: 1,
+            max_depth: 1,
+        };
+        let result = std::panic::catch_unwind(|| with_limits(limits, || panic!("oops")));
+        assert!(result.is_err());
+        assert_eq!(current(), Limits::default());
+    }
+}
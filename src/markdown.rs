@@ -10,44 +10,96 @@ use similar::TextDiff;
 
 use crate::error::Result;
 use crate::eval::Session;
-use crate::transcript;
+use crate::transcript::{self, Change};
 
 /// Extract J input and output from Markdown; run the commands; return a diff
 /// reflecting differences in output.
 ///
+/// Boxes are drawn with ASCII characters.
+///
 /// If there are no differences the result is an empty string.
 pub fn diff_file(markdown_path: &Path) -> Result<String> {
-    let markdown = std::fs::read_to_string(&markdown_path)?;
-    let output = Document::parse(&markdown)?
-        .run(&mut Session::new())?
-        .reassemble();
-    let text_diff = TextDiff::from_lines(&markdown, &output);
-    let old_name = format!("{}", markdown_path.display()).replace('\\', "/");
-    let new_name = format!("{}.new", old_name);
-    Ok(text_diff
-        .unified_diff()
-        .context_radius(8)
-        .header(&old_name, &new_name)
-        .to_string())
+    let mut session = Session::new();
+    session.set_ascii_boxes(true);
+    check_file(markdown_path, &mut session).map(|check| check.diff)
+}
+
+/// The result of checking whether the examples in a Markdown file are up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// A unified diff of the changes that would be made by updating the file;
+    /// empty if it's up to date.
+    pub diff: String,
+    /// The number of J examples (code blocks) in the file.
+    pub examples: usize,
+    /// The number of examples whose output would change.
+    pub changed: usize,
+    /// Each sentence whose output would change.
+    pub changes: Vec<Change>,
 }
 
-/// Run the J source embeddet in a Markdown file and update the file with the
-/// results of executing the J sentences.
-pub fn update_file(markdown_path: &Path) -> Result<()> {
-    let markdown = std::fs::read_to_string(&markdown_path)?;
-    let output = Document::parse(&markdown)?
-        .run(&mut Session::new())?
-        .reassemble();
+/// Run the J examples in a Markdown file in the given session, and report how
+/// many of them are out of date, along with a diff.
+pub fn check_file(markdown_path: &Path, session: &mut Session) -> Result<Check> {
+    let markdown = std::fs::read_to_string(markdown_path)?;
+    run_examples(markdown_path, &markdown, session).map(|(check, _output)| check)
+}
+
+/// Run the J source embeddet in a Markdown file, in the given session, and
+/// update the file with the results of executing the J sentences.
+///
+/// If any examples changed and `backup` is true, the original file is kept
+/// with a `.old` suffix; otherwise it's overwritten in place.
+///
+/// Returns a [Check] describing the changes that were made.
+pub fn update_file(markdown_path: &Path, session: &mut Session, backup: bool) -> Result<Check> {
+    let markdown = std::fs::read_to_string(markdown_path)?;
+    let (check, output) = run_examples(markdown_path, &markdown, session)?;
     if output != markdown {
-        let backup_path = PathBuf::from(format!("{}.old", markdown_path.display()));
-        fs::rename(markdown_path, backup_path)?;
+        if backup {
+            let backup_path = PathBuf::from(format!("{}.old", markdown_path.display()));
+            fs::rename(markdown_path, backup_path)?;
+        }
         fs::write(markdown_path, output.as_bytes())?;
     }
-    Ok(())
+    Ok(check)
+}
+
+/// Run the examples in a Markdown document, returning a [Check] comparing it
+/// to the updated document, and the text of the updated document.
+fn run_examples(
+    markdown_path: &Path,
+    markdown: &str,
+    session: &mut Session,
+) -> Result<(Check, String)> {
+    let doc = Document::parse(markdown)?;
+    let (updated, changes) = doc.run(session)?;
+    let output = updated.reassemble();
+    let text_diff = TextDiff::from_lines(markdown, &output);
+    let old_name = format!("{}", markdown_path.display()).replace('\\', "/");
+    let new_name = format!("{}.new", old_name);
+    let diff = text_diff
+        .unified_diff()
+        .context_radius(8)
+        .header(&old_name, &new_name)
+        .to_string();
+    let examples = doc.examples().count();
+    let changed = doc
+        .examples()
+        .zip(updated.examples())
+        .filter(|(old, new)| old != new)
+        .count();
+    let check = Check {
+        diff,
+        examples,
+        changed,
+        changes,
+    };
+    Ok((check, output))
 }
 
 pub fn extract_transcript(markdown_path: &Path) -> Result<String> {
-    let markdown = std::fs::read_to_string(&markdown_path)?;
+    let markdown = std::fs::read_to_string(markdown_path)?;
     Document::parse(&markdown)?.extract_transcript()
 }
 
@@ -118,33 +170,47 @@ impl<'markdown> Document<'markdown> {
         Ok(Document { chunks, crlf })
     }
 
+    /// Iterate the text of all the J examples.
+    fn examples(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().filter_map(|chunk| match chunk {
+            Chunk::J(example, _) => Some(example.as_str()),
+            Chunk::Other(_) => None,
+        })
+    }
+
     /// Return the J transcript of all the examples.
     fn extract_transcript(&self) -> Result<String> {
-        let mut s = String::new();
-        for chunk in &self.chunks {
-            if let Chunk::J(example, _) = chunk {
-                s.push_str(example)
-            }
-        }
-        Ok(s)
+        Ok(self.examples().collect())
     }
 
-    /// Run all the examples and return a new Document with updated output.
-    pub fn run(&self, session: &mut Session) -> Result<Document> {
+    /// Run all the examples and return a new Document with updated output,
+    /// and the sentences whose output changed.
+    ///
+    /// So that random numbers are the same every time, the session's random
+    /// seed is reset at the start of the document, and for each fenced block
+    /// with a seed in its info string, such as ```` ```j seed=42 ````.
+    pub fn run(&self, session: &mut Session) -> Result<(Document<'_>, Vec<Change>)> {
         let mut output = Vec::new();
+        let mut changes = Vec::new();
+        session.set_random_seed(DEFAULT_SEED);
         for chunk in &self.chunks {
             match chunk {
                 Chunk::J(j, kind) => {
-                    let fragment = transcript::rerun(session, j)?;
-                    output.push(Chunk::J(fragment, kind.clone()))
+                    if let Some(seed) = fence_seed(kind) {
+                        session.set_random_seed(seed);
+                    }
+                    let (fragment, chunk_changes) = transcript::rerun_with_changes(session, j)?;
+                    output.push(Chunk::J(fragment, kind.clone()));
+                    changes.extend(chunk_changes);
                 }
                 Chunk::Other(text) => output.push(Chunk::Other(text)),
             }
         }
-        Ok(Document {
+        let doc = Document {
             chunks: output,
             crlf: self.crlf,
-        })
+        };
+        Ok((doc, changes))
     }
 
     /// Reassemble text and examples into a Markdown doc.
@@ -174,6 +240,19 @@ impl<'markdown> Document<'markdown> {
     }
 }
 
+/// The random seed at the start of each document.
+const DEFAULT_SEED: u64 = 0;
+
+/// The random seed given by `seed=N` in the info string of a fenced code block.
+fn fence_seed(kind: &CodeBlockKind) -> Option<u64> {
+    match kind {
+        CodeBlockKind::Fenced(tags) => tags
+            .split_whitespace()
+            .find_map(|tag| tag.strip_prefix("seed=")?.parse().ok()),
+        CodeBlockKind::Indented => None,
+    }
+}
+
 fn reinsert_indents(ijs: &str) -> String {
     let mut s = String::new();
     for (i, l) in ijs.lines().enumerate() {
@@ -237,7 +316,7 @@ And closing text.
     #[test]
     fn update_markdown_with_crlf_inserts_crlf() -> Result<()> {
         let doc = Document::parse("```\r\n   # 1 2 3\r\n```\r\nfin.\r\n")?;
-        let updated = doc.run(&mut Session::new())?;
+        let (updated, _changes) = doc.run(&mut Session::new())?;
         assert_eq!(
             updated.reassemble(),
             "```\r\n   # 1 2 3\r\n3\r\n```\r\nfin.\r\n"
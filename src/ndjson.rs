@@ -0,0 +1,113 @@
+// Copyright 2022 Martin Pool
+
+//! Evaluate sentences and describe each result as a line of JSON, for tools
+//! that run rsj as a subprocess.
+//!
+//! Each line is an object like
+//! `{"input":"1 + 2","output":"3","error":null,"message":null}`. If the
+//! sentence fails, `output` is null, `error` names the kind of error, such as
+//! `"Domain"` or `"Parse"`, as given by
+//! [ErrorKind](crate::error::ErrorKind), and `message` describes the error as
+//! it's shown interactively, with any details. A sentence that gives no
+//! result, such as an assignment or a comment, has an empty `output`.
+//!
+//! See <https://github.com/ndjson/ndjson-spec>.
+
+use std::fmt::Write;
+
+use crate::eval::Session;
+
+/// Evaluate one line in the session, and return a JSON object describing
+/// the result, without a trailing newline.
+pub fn eval_line(session: &mut Session, line: &str) -> String {
+    let (output, error, message) = match session.eval_line(line) {
+        Ok(word) => (Some(session.format_result(Ok(word))), None, None),
+        Err(err) => (
+            None,
+            Some(format!("{:?}", err.kind())),
+            Some(format!("{:?}", err)),
+        ),
+    };
+    format!(
+        "{{\"input\":{},\"output\":{},\"error\":{},\"message\":{}}}",
+        json_string(line),
+        json_or_null(output.as_deref()),
+        json_or_null(error.as_deref()),
+        json_or_null(message.as_deref()),
+    )
+}
+
+fn json_or_null(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".to_owned(), json_string)
+}
+
+/// Quote a string as JSON.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quote_strings() {
+        assert_eq!(json_string("abc"), r#""abc""#);
+        assert_eq!(json_string("'a\"b'"), r#""'a\"b'""#);
+        assert_eq!(json_string("a\\b"), r#""a\\b""#);
+        assert_eq!(json_string("1 2\n3 4"), r#""1 2\n3 4""#);
+        assert_eq!(json_string("\x01┌"), r#""\u0001┌""#);
+    }
+
+    #[test]
+    fn results_and_errors() {
+        let mut session = Session::new();
+        assert_eq!(
+            eval_line(&mut session, "2 2 $ 1 2 3 4"),
+            r#"{"input":"2 2 $ 1 2 3 4","output":"1 2\n3 4","error":null,"message":null}"#
+        );
+        assert_eq!(
+            eval_line(&mut session, "1 + 'a'"),
+            r#"{"input":"1 + 'a'","output":null,"error":"Domain","message":"Domain"}"#
+        );
+        assert_eq!(
+            eval_line(&mut session, "a =: 3"),
+            r#"{"input":"a =: 3","output":"","error":null,"message":null}"#
+        );
+        assert_eq!(
+            eval_line(&mut session, "a * 2"),
+            r#"{"input":"a * 2","output":"6","error":null,"message":null}"#
+        );
+    }
+
+    #[test]
+    fn errors_are_named_by_kind() {
+        let mut session = Session::new();
+        assert_eq!(
+            eval_line(&mut session, ",/ ''"),
+            r#"{"input":",/ ''","output":null,"error":"Unimplemented","message":"Unimplemented(\"identity element for u/ of an empty list\")"}"#
+        );
+        assert_eq!(
+            eval_line(&mut session, "'abc"),
+            r#"{"input":"'abc","output":null,"error":"Parse","message":"OpenQuote"}"#
+        );
+        assert_eq!(
+            eval_line(&mut session, "1 {. :: 2"),
+            r#"{"input":"1 {. :: 2","output":null,"error":"Parse","message":"Unexpected(':')"}"#
+        );
+    }
+}
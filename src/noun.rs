@@ -3,24 +3,203 @@
 //! Nouns (J objects.)
 
 use std::fmt;
+use std::str::FromStr;
 
+use ndarray::{ArrayD, IxDyn, Slice};
 use num_complex::Complex64;
 
 use crate::array::Array;
 use crate::atom::Atom;
+use crate::error::{Error, Result};
+use crate::eval::Session;
+use crate::scan::scan_sentence;
+use crate::word::Word;
 
+/// A J noun: either a single atom, or an array of atoms of any rank,
+/// including tables.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Noun {
     Atom(Atom),
     Array(Array),
 }
 
+impl Noun {
+    /// Construct a list of characters from a string.
+    pub fn char_list(s: &str) -> Noun {
+        let chars: Array = s.bytes().map(Atom::Char).collect();
+        Noun::Array(chars.with_fill(Atom::Char(b' ')))
+    }
+
+    /// The atom used to pad this noun: the fill for the type of its atoms,
+    /// as given by [Atom::fill] and [Array::fill].
+    pub fn fill(&self) -> Atom {
+        match self {
+            Noun::Atom(a) => a.fill(),
+            Noun::Array(a) => a.fill(),
+        }
+    }
+
+    /// Return the items of the noun: the cells along the leading axis, or the
+    /// noun itself if it's an atom.
+    pub fn items(&self) -> Vec<Noun> {
+        match self {
+            Noun::Atom(_) => vec![self.clone()],
+            Noun::Array(a) => a.iter_items().collect(),
+        }
+    }
+
+    /// Split the noun into cells of the given rank, returning the frame (the
+    /// leading axes that are not part of the cells) and the cells.
+    ///
+    /// `cell_rank` must be no more than the rank of the noun.
+    pub fn cells(&self, cell_rank: usize) -> (&[usize], Vec<Noun>) {
+        let dims = self.dims();
+        let (frame, cell_shape) = dims.split_at(dims.len() - cell_rank);
+        let cell_len = cell_shape.iter().product::<usize>();
+        let n_cells = frame.iter().product::<usize>();
+        let atoms: Vec<Atom> = self.iter_atoms().cloned().collect();
+        let cells = (0..n_cells)
+            .map(|i| {
+                let cell_atoms = atoms[i * cell_len..(i + 1) * cell_len].to_vec();
+                Noun::from(
+                    Array::from_shape_vec(cell_shape, cell_atoms)
+                        .expect("cell atoms match cell shape"),
+                )
+            })
+            .collect();
+        (frame, cells)
+    }
+
+    /// Return the length of each axis: empty for an atom.
+    pub fn dims(&self) -> &[usize] {
+        match self {
+            Noun::Atom(_) => &[],
+            Noun::Array(a) => a.dims(),
+        }
+    }
+
+    /// The name of the type of the atoms in the noun, for debugging: one of
+    /// `"numeric"` for real numbers, `"complex"` if any number has an
+    /// imaginary part, `"character"`, `"boxed"`, or `"empty"` for an array with
+    /// no atoms, whose type is not known.
+    pub fn type_name(&self) -> &'static str {
+        match self.iter_atoms().next() {
+            None => "empty",
+            Some(Atom::Char(_)) => "character",
+            Some(Atom::Boxed(_)) => "boxed",
+            Some(Atom::Complex(_)) => {
+                if self
+                    .iter_atoms()
+                    .any(|a| matches!(a, Atom::Complex(c) if c.im != 0.0))
+                {
+                    "complex"
+                } else {
+                    "numeric"
+                }
+            }
+        }
+    }
+
+    /// Assemble a collection of cells into a single noun, with `frame` as the
+    /// leading axes.
+    ///
+    /// If the cells are not all the same shape, they're brought up to the same
+    /// rank by adding leading unit axes, and then each is padded with fill
+    /// atoms to the largest length along each axis.
+    ///
+    /// All the non-empty cells must contain the same type of atom.
+    pub fn assemble(frame: &[usize], cells: &[Noun]) -> Result<Noun> {
+        debug_assert_eq!(frame.iter().product::<usize>(), cells.len());
+        let mut first_atom = None;
+        for atom in cells.iter().flat_map(|c| c.iter_atoms().take(1)) {
+            match first_atom {
+                None => first_atom = Some(atom),
+                Some(first) if !first.same_type(atom) => return Err(Error::Domain),
+                _ => (),
+            }
+        }
+        let fill = first_atom.map_or_else(Atom::zero, Atom::fill);
+        let cell_rank = cells.iter().map(|c| c.dims().len()).max().unwrap_or(0);
+        let mut cell_shape = vec![0; cell_rank];
+        for cell in cells {
+            let dims = cell.dims();
+            for (i, &d) in dims.iter().enumerate() {
+                let axis = cell_rank - dims.len() + i;
+                cell_shape[axis] = cell_shape[axis].max(d);
+            }
+        }
+        if cells.is_empty() {
+            cell_shape.clear();
+        }
+        let mut atoms = Vec::with_capacity(cells.len() * cell_shape.iter().product::<usize>());
+        for cell in cells {
+            if cell.dims().len() == cell_rank && cell.dims() == cell_shape {
+                atoms.extend(cell.iter_atoms().cloned());
+            } else {
+                let mut padded_dims = vec![1; cell_rank - cell.dims().len()];
+                padded_dims.extend_from_slice(cell.dims());
+                let source = ArrayD::from_shape_vec(
+                    IxDyn(&padded_dims),
+                    cell.iter_atoms().cloned().collect(),
+                )
+                .expect("cell shape matches its atoms");
+                let mut padded = ArrayD::from_elem(IxDyn(&cell_shape), fill.clone());
+                padded
+                    .slice_each_axis_mut(|ax| Slice::from(0..padded_dims[ax.axis.index()]))
+                    .assign(&source);
+                atoms.extend(padded);
+            }
+        }
+        let mut shape = frame.to_vec();
+        shape.extend_from_slice(&cell_shape);
+        Array::from_shape_vec(&shape, atoms).map(Noun::from)
+    }
+
+    /// Iterate by-reference all the atoms in the noun: either the noun itself
+    /// if it's an atom, or all the atoms of the array, in row-major order.
+    pub fn iter_atoms(&self) -> Box<dyn Iterator<Item = &Atom> + '_> {
+        match self {
+            Noun::Atom(a) => Box::new(std::iter::once(a)),
+            Noun::Array(array) => Box::new(array.iter_atoms()),
+        }
+    }
+
+    /// The real numbers in the noun, in row-major order, discarding its
+    /// shape.
+    ///
+    /// Returns [Error::Domain] if any atom is a complex number with a nonzero
+    /// imaginary part, a character, or a box. An empty array gives an empty
+    /// list, whatever its type.
+    pub fn to_vec_f64(&self) -> Result<Vec<f64>> {
+        self.iter_atoms()
+            .map(|atom| atom.try_to_f64().ok_or(Error::Domain))
+            .collect()
+    }
+}
+
 impl From<Atom> for Noun {
     fn from(atom: Atom) -> Noun {
         Noun::Atom(atom)
     }
 }
 
+/// Convert an array into a noun, unwrapping rank 0 arrays into atoms.
+impl From<Array> for Noun {
+    fn from(array: Array) -> Noun {
+        if array.dims().is_empty() {
+            Noun::Atom(
+                array
+                    .iter_atoms()
+                    .next()
+                    .expect("rank 0 array has one atom")
+                    .clone(),
+            )
+        } else {
+            Noun::Array(array)
+        }
+    }
+}
+
 impl From<Vec<Atom>> for Noun {
     fn from(vec: Vec<Atom>) -> Noun {
         Noun::Array(Array::from(vec))
@@ -39,6 +218,28 @@ impl From<Complex64> for Noun {
     }
 }
 
+/// Parse a literal noun, such as `1 2 3`, `'abc'`, or `(1 2)`.
+///
+/// Returns [Error::SyntaxError] if the string contains any verbs or modifiers,
+/// or doesn't form a single noun.
+impl FromStr for Noun {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Noun> {
+        let sentence = scan_sentence(s)?;
+        if !sentence
+            .iter()
+            .all(|w| matches!(w, Word::Noun(_) | Word::OpenParen | Word::CloseParen))
+        {
+            return Err(Error::SyntaxError);
+        }
+        match Session::new().eval_sentence(&sentence)? {
+            Some(Word::Noun(noun)) => Ok(noun),
+            _ => Err(Error::SyntaxError),
+        }
+    }
+}
+
 impl fmt::Display for Noun {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
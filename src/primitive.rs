@@ -5,36 +5,118 @@
 // See https://code.jsoftware.com/wiki/Vocabulary/Words#Primitives
 
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt;
+use std::rc::Rc;
 
 use bstr::BStr;
 use fmt::Formatter;
 
 use crate::array::Array;
-use crate::atom::Atom;
+use crate::atom::{Atom, EXACT_INTEGER_LIMIT};
 use crate::error::{Error, Result};
 use crate::noun::Noun;
-use crate::verb::Verb;
+use crate::random;
+use crate::verb::{dyad_at_rank, monad_at_rank, Rank, Verb};
 
 /// A builtin primitive verb, such as `-` or `<.`.
 pub struct Primitive(&'static [u8], Monad, Dyad);
 
 // All implemented primitives.
-pub const DOLLAR: Primitive = Primitive(b"$", Monad::Infinite(shape_of), Dyad::Unimplemented);
-pub const MINUS: Primitive = Primitive(b"-", Monad::Zero(negate), Dyad::Zero(minus));
+pub const DOLLAR: Primitive = Primitive(b"$", Monad::Infinite(shape_of), Dyad::Infinite(reshape));
+pub const MINUS: Primitive = Primitive(b"-", Monad::Zero(negate), Dyad::Exact(minus, |x, y| x - y));
 pub const MINUS_DOT: Primitive = Primitive(b"-.", Monad::Zero(not), Dyad::Unimplemented);
-pub const NUMBER: Primitive = Primitive(b"#", Monad::Infinite(tally), Dyad::Unimplemented);
-pub const PLUS: Primitive = Primitive(b"+", Monad::Unimplemented, Dyad::Zero(plus));
+pub const NUMBER: Primitive = Primitive(b"#", Monad::Infinite(tally), Dyad::Infinite(copy));
+pub const PLUS: Primitive = Primitive(b"+", Monad::Unimplemented, Dyad::Exact(plus, |x, y| x + y));
 
 pub const PRIMITIVES: &[Primitive] = &[
     DOLLAR,
     MINUS,
     MINUS_DOT,
     NUMBER,
+    Primitive(b"#.", Monad::Infinite(base_2), Dyad::Infinite(base)),
+    Primitive(b"#:", Monad::Infinite(antibase_2), Dyad::Infinite(antibase)),
     Primitive(b"%", Monad::Zero(reciprocal), Dyad::Zero(divide)),
-    Primitive(b"*", Monad::Zero(signum), Dyad::Zero(times)),
+    Primitive(b"%.", Monad::Infinite(matrix_inverse), Dyad::Unimplemented),
+    Primitive(b"*", Monad::Zero(signum), Dyad::Exact(times, |x, y| x * y)),
+    Primitive(b"*.", Monad::Unimplemented, Dyad::Zero(lcm)),
+    Primitive(b"*:", Monad::Zero(square), Dyad::Unimplemented),
+    Primitive(b"%:", Monad::Zero(square_root), Dyad::Zero(root)),
     PLUS,
-    Primitive(b"i.", Monad::Infinite(integers), Dyad::Unimplemented),
+    Primitive(b"+.", Monad::Unimplemented, Dyad::Zero(gcd)),
+    Primitive(b"+:", Monad::Zero(double), Dyad::Unimplemented),
+    Primitive(b"-:", Monad::Zero(halve), Dyad::Infinite(match_nouns)),
+    Primitive(b"i.", Monad::Infinite(integers), Dyad::Infinite(index_of)),
+    Primitive(b"e.", Monad::Unimplemented, Dyad::Infinite(member)),
+    Primitive(b"i:", Monad::Infinite(steps), Dyad::Unimplemented),
+    Primitive(
+        b"q:",
+        Monad::Infinite(prime_factors),
+        Dyad::Infinite(prime_exponents),
+    ),
+    Primitive(b"?", Monad::Zero(roll), Dyad::Infinite(deal)),
+    Primitive(b",", Monad::Infinite(ravel), Dyad::Infinite(append)),
+    Primitive(b",:", Monad::Infinite(itemize), Dyad::Infinite(laminate)),
+    Primitive(b";:", Monad::Infinite(words), Dyad::Unimplemented),
+    Primitive(
+        b"\":",
+        Monad::Infinite(default_format),
+        Dyad::Infinite(format),
+    ),
+    Primitive(b"=", Monad::Infinite(self_classify), Dyad::Zero(equal)),
+    Primitive(b"<", Monad::Infinite(box_noun), Dyad::Zero(less)),
+    Primitive(b"<.", Monad::Zero(floor), Dyad::Zero(lesser_of)),
+    Primitive(b"<:", Monad::Zero(decrement), Dyad::Zero(less_or_equal)),
+    Primitive(b">", Monad::ZeroNoun(open), Dyad::Zero(larger)),
+    Primitive(b">.", Monad::Zero(ceiling), Dyad::Zero(larger_of)),
+    Primitive(b">:", Monad::Zero(increment), Dyad::Zero(larger_or_equal)),
+    Primitive(b"^", Monad::Zero(exponential), Dyad::Zero(power)),
+    Primitive(b"^.", Monad::Zero(natural_log), Dyad::Zero(logarithm)),
+    Primitive(b"~:", Monad::Infinite(nub_sieve), Dyad::Zero(not_equal)),
+    Primitive(b"~.", Monad::Infinite(nub), Dyad::Unimplemented),
+    Primitive(b"|.", Monad::Infinite(reverse), Dyad::Infinite(rotate)),
+    Primitive(
+        b"|:",
+        Monad::Infinite(transpose),
+        Dyad::Infinite(transpose_axes),
+    ),
+    Primitive(b"{", Monad::Unimplemented, Dyad::Infinite(from)),
+    Primitive(b"/:", Monad::Infinite(grade_up), Dyad::Infinite(sort_up)),
+    Primitive(b"{.", Monad::Infinite(head), Dyad::Unimplemented),
+    Primitive(b"}.", Monad::Infinite(behead), Dyad::Unimplemented),
+    Primitive(b"{:", Monad::Infinite(tail), Dyad::Unimplemented),
+    Primitive(b"}:", Monad::Infinite(curtail), Dyad::Unimplemented),
+    Primitive(b"[", Monad::Infinite(same), Dyad::Infinite(left)),
+    Primitive(b"]", Monad::Infinite(same), Dyad::Infinite(right)),
+];
+
+/// Pairs of primitives where each, as a monad, is the inverse of the other.
+const INVERSES: &[(&[u8], &[u8])] = &[
+    (b"-", b"-"),
+    (b"%", b"%"),
+    (b"+:", b"-:"),
+    (b"*:", b"%:"),
+    (b"#.", b"#:"),
+    (b"<:", b">:"),
+    (b"^", b"^."),
+    (b"|.", b"|."),
+    (b"|:", b"|:"),
+    (b"[", b"["),
+    (b"]", b"]"),
+];
+
+/// The identity elements of primitives, when used as dyads.
+const IDENTITIES: &[(&[u8], f64)] = &[
+    (b"+", 0.0),
+    (b"-", 0.0),
+    (b"*", 1.0),
+    (b"%", 1.0),
+    (b"^", 1.0),
+    (b"+.", 0.0),
+    (b"*.", 1.0),
+    (b"<.", f64::INFINITY),
+    (b">.", f64::NEG_INFINITY),
 ];
 
 impl Primitive {
@@ -42,6 +124,19 @@ impl Primitive {
         self.0.into()
     }
 
+    /// Return the primitive whose monad undoes this primitive's monad, if
+    /// there is one.
+    pub fn inverse(&self) -> Option<&'static Primitive> {
+        INVERSES
+            .iter()
+            .find_map(|&(a, b)| match self.0 {
+                n if n == a => Some(b),
+                n if n == b => Some(a),
+                _ => None,
+            })
+            .and_then(|name| Primitive::by_name(&name).ok())
+    }
+
     pub fn by_name<S>(s: &S) -> Result<&'static Primitive>
     where
         S: AsRef<[u8]>,
@@ -54,10 +149,37 @@ impl Primitive {
         }
         Err(Error::Unimplemented("primitive".into()))
     }
+
+    /// True if this primitive's dyad, applied to integers in x and y, gives
+    /// a result that had to be rounded because it's too large to represent
+    /// exactly.
+    ///
+    /// Only dyads with an exact integer form, such as `+`, are checked; other
+    /// verbs give false.
+    pub(crate) fn dyad_rounds_integers(&self, x: &Noun, y: &Noun) -> bool {
+        let Dyad::Exact(f, exact) = self.2 else {
+            return false;
+        };
+        let as_integer = |atom: &Atom| atom.try_to_f64().filter(|_| atom.is_exact_integer());
+        let rounded = dyad_at_rank(x, y, (Rank::Finite(0), Rank::Finite(0)), |x, y| {
+            let rounded = match (x, y) {
+                (Noun::Atom(x), Noun::Atom(y)) => match (as_integer(x), as_integer(y)) {
+                    (Some(a), Some(b)) => f(x, y)
+                        .ok()
+                        .and_then(|r| r.try_to_f64())
+                        .is_some_and(|r| r as i128 != exact(a as i128, b as i128)),
+                    _ => false,
+                },
+                _ => false,
+            };
+            Ok(Noun::from(Atom::from(rounded as usize)))
+        });
+        rounded.is_ok_and(|r| r.iter_atoms().any(|a| !a.is_zero()))
+    }
 }
 
 impl Verb for Primitive {
-    fn display(&self) -> Cow<str> {
+    fn display(&self) -> Cow<'_, str> {
         Cow::Owned(format!("{}", self.name()))
     }
 
@@ -68,6 +190,31 @@ impl Verb for Primitive {
     fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
         self.2.apply(x, y)
     }
+
+    fn as_primitive(&self) -> Option<&Primitive> {
+        Some(self)
+    }
+
+    fn monad_rank(&self) -> Rank {
+        match self.1 {
+            Monad::Zero(_) | Monad::ZeroNoun(_) => Rank::Finite(0),
+            _ => Rank::Infinite,
+        }
+    }
+
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        match Primitive::inverse(self) {
+            Some(inverse) => Ok(Rc::new(inverse)),
+            None => Err(Error::Domain),
+        }
+    }
+
+    fn identity(&self) -> Option<Atom> {
+        IDENTITIES
+            .iter()
+            .find(|(name, _)| *name == self.0)
+            .map(|&(_, e)| Atom::from(e))
+    }
 }
 
 impl fmt::Display for Primitive {
@@ -94,6 +241,9 @@ impl std::cmp::PartialEq for Primitive {
 enum Monad {
     /// A monad that applies per-atom.
     Zero(fn(&Atom) -> Result<Atom>),
+    /// A monad that applies per-atom, giving a noun for each atom, which are
+    /// assembled into a result with y's shape as the frame.
+    ZeroNoun(fn(&Atom) -> Result<Noun>),
     Infinite(fn(&Noun) -> Result<Noun>),
     Unimplemented,
     // TODO: One, Two, ...
@@ -103,12 +253,17 @@ impl Monad {
     /// Apply this monad to the y noun, at the appropriate rank.
     fn apply(&self, y: &Noun) -> Result<Noun> {
         match self {
-            Monad::Zero(f) => match y {
-                Noun::Atom(a) => f(a).map(Noun::Atom),
-                Noun::Array(array) => Ok(Noun::Array(Array::from_vec(
-                    array.iter_atoms().map(f).collect::<Result<Vec<Atom>>>()?,
-                ))),
-            },
+            Monad::Zero(f) => {
+                let f = |a: &Atom| f(a).and_then(not_nan);
+                match y {
+                    Noun::Atom(a) => f(a).map(Noun::Atom),
+                    Noun::Array(array) => array.try_map(f).map(Noun::Array),
+                }
+            }
+            Monad::ZeroNoun(f) => {
+                let cells = y.iter_atoms().map(f).collect::<Result<Vec<Noun>>>()?;
+                Noun::assemble(y.dims(), &cells)
+            }
             Monad::Infinite(f) => f(y),
             Monad::Unimplemented => Err(Error::Unimplemented("Monad::Unimplemented".into())),
         }
@@ -121,6 +276,11 @@ enum Dyad {
     // a different representation.
     /// Per atom on both sides (0, 0).
     Zero(fn(&Atom, &Atom) -> Result<Atom>),
+    /// Per atom, like [Dyad::Zero], for arithmetic that also has an exact
+    /// form on integers, which shows whether a result was rounded.
+    Exact(fn(&Atom, &Atom) -> Result<Atom>, fn(i128, i128) -> i128),
+    /// Applied to the whole of both arguments (_, _).
+    Infinite(fn(&Noun, &Noun) -> Result<Noun>),
     Unimplemented,
 }
 
@@ -129,47 +289,57 @@ impl Dyad {
         // TODO: This code for working out how to apply element-at-a-time etc
         // probably should be generic to all verbs, not only primitives.
         match self {
-            Dyad::Zero(f) => match (x, y) {
-                (Noun::Atom(ax), Noun::Atom(ay)) => f(ax, ay).map(Noun::from),
-                (Noun::Array(ax), Noun::Array(ay)) => {
-                    // element-wise
-                    // TODO: This is actually too specific: it's OK for the arrays to be
-                    // different shapes as long as they "agree":
-                    // https://code.jsoftware.com/wiki/Vocabulary/Agreement
-                    if ax.shape() == ay.shape() {
-                        Ok(Noun::Array(Array::from_vec(
-                            ax.iter_atoms()
-                                .zip(ay.iter_atoms())
-                                .map(|(ix, iy)| f(ix, iy))
-                                .collect::<Result<Vec<Atom>>>()?,
-                        )))
-                    } else {
-                        Err(Error::Length)
+            Dyad::Zero(f) | Dyad::Exact(f, _) => {
+                let f = |x: &Atom, y: &Atom| f(x, y).and_then(not_nan);
+                match (x, y) {
+                    (Noun::Atom(ax), Noun::Atom(ay)) => f(ax, ay).map(Noun::from),
+                    (Noun::Array(ax), Noun::Array(ay)) => {
+                        if ax.dims() == ay.dims() {
+                            // element-wise
+                            let mut iy = ay.iter_atoms();
+                            ax.try_map(|ix| f(ix, iy.next().unwrap())).map(Noun::Array)
+                        } else {
+                            // Pair up atoms of the arrays as long as they "agree":
+                            // https://code.jsoftware.com/wiki/Vocabulary/Agreement
+                            dyad_at_rank(x, y, (Rank::Finite(0), Rank::Finite(0)), |x, y| {
+                                self.apply(x, y)
+                            })
+                        }
+                    }
+                    // The atom is paired with every atom of the array, and the
+                    // result has the shape of the array.
+                    (Noun::Atom(ax), Noun::Array(ay)) => {
+                        ay.try_map(|iy| f(ax, iy)).map(Noun::Array)
+                    }
+                    (Noun::Array(ax), Noun::Atom(ay)) => {
+                        ax.try_map(|ix| f(ix, ay)).map(Noun::Array)
                     }
                 }
-                (Noun::Atom(ax), Noun::Array(ay)) => Ok(Noun::Array(Array::from_vec(
-                    ay.iter_atoms()
-                        .map(|iy| f(ax, iy))
-                        .collect::<Result<Vec<Atom>>>()?,
-                ))),
-                (Noun::Array(ax), Noun::Atom(ay)) => Ok(Noun::Array(Array::from_vec(
-                    ax.iter_atoms()
-                        .map(|ix| f(ix, ay))
-                        .collect::<Result<Vec<Atom>>>()?,
-                ))),
-            },
+            }
+            Dyad::Infinite(f) => f(x, y),
             &Dyad::Unimplemented => Err(Error::Unimplemented("Dyad::Unimplemented".into())),
         }
     }
 }
 
+/// J has no NaN, so an operation that would produce one, such as `_ - _`, is a
+/// domain error.
+fn not_nan(a: Atom) -> Result<Atom> {
+    match a {
+        Atom::Complex(c) if c.is_nan() => Err(Error::Domain),
+        a => Ok(a),
+    }
+}
+
 fn negate(y: &Atom) -> Result<Atom> {
     match y {
         Atom::Complex(a) => Ok(Atom::Complex(-a)),
+        Atom::Char(_) | Atom::Boxed(_) => Err(Error::Domain),
     }
 }
 
 fn signum(y: &Atom) -> Result<Atom> {
+    y.to_complex()?;
     if let Some(y) = y.try_to_f64() {
         // J signum is 0 for 0, while Rust signum is 1
         if y == 0.0 {
@@ -184,18 +354,18 @@ fn signum(y: &Atom) -> Result<Atom> {
 }
 
 fn minus(x: &Atom, y: &Atom) -> Result<Atom> {
-    Ok(Atom::Complex(x.to_complex() - y.to_complex()))
+    Ok(Atom::Complex(x.to_complex()? - y.to_complex()?))
 }
 
 /// Add atoms.
 fn plus(x: &Atom, y: &Atom) -> Result<Atom> {
-    Ok(Atom::Complex(x.to_complex() + y.to_complex()))
+    Ok(Atom::Complex(x.to_complex()? + y.to_complex()?))
 }
 
 /// `x % y` divide
 fn divide(x: &Atom, y: &Atom) -> Result<Atom> {
-    let x = x.to_complex();
-    let y = y.to_complex();
+    let x = x.to_complex()?;
+    let y = y.to_complex()?;
     // As a special case in J, `0 % 0 = 0`.
     if x.re == 0.0 && x.im == 0.0 {
         // TODO: Maybe `_0 % __` should be 0?
@@ -215,15 +385,445 @@ fn divide(x: &Atom, y: &Atom) -> Result<Atom> {
 }
 
 fn times(x: &Atom, y: &Atom) -> Result<Atom> {
+    let (cx, cy) = (x.to_complex()?, y.to_complex()?);
     if x.is_zero() || y.is_zero() {
         // Multiplying even infinity by 0 is 0.
         // https://code.jsoftware.com/wiki/Vocabulary/star
         Ok(Atom::zero())
+    } else if let (Some(x), Some(y)) = (x.try_to_f64(), y.try_to_f64()) {
+        // Multiply reals directly, because complex multiplication of
+        // infinities gives an imaginary part of `_ * 0`, which is NaN.
+        Ok((x * y).into())
+    } else {
+        Ok(Atom::Complex(cx * cy))
+    }
+}
+
+/// `+: y`: y times 2.
+fn double(y: &Atom) -> Result<Atom> {
+    plus(y, y)
+}
+
+/// `-: y`: y divided by 2.
+fn halve(y: &Atom) -> Result<Atom> {
+    divide(y, &2.0.into())
+}
+
+/// `*: y`: y times y.
+fn square(y: &Atom) -> Result<Atom> {
+    times(y, y)
+}
+
+/// `%: y`: the square root of y, which is complex if y is negative.
+fn square_root(y: &Atom) -> Result<Atom> {
+    match y.try_to_f64() {
+        Some(y) if y >= 0.0 => Ok(y.sqrt().into()),
+        _ => Ok(y.to_complex()?.sqrt().into()),
+    }
+}
+
+/// `x %: y`: the x'th root of y.
+fn root(x: &Atom, y: &Atom) -> Result<Atom> {
+    match x.try_to_f64() {
+        Some(2.0) => square_root(y),
+        _ => power(y, &reciprocal(x)?),
+    }
+}
+
+/// `>: y`: y plus 1.
+fn increment(y: &Atom) -> Result<Atom> {
+    Ok(Atom::Complex(y.to_complex()? + 1.0))
+}
+
+/// `<: y`: y minus 1.
+fn decrement(y: &Atom) -> Result<Atom> {
+    Ok(Atom::Complex(y.to_complex()? - 1.0))
+}
+
+/// `^ y`: e to the power y.
+fn exponential(y: &Atom) -> Result<Atom> {
+    match y.try_to_f64() {
+        Some(y) => Ok(y.exp().into()),
+        None => Ok(y.to_complex()?.exp().into()),
+    }
+}
+
+/// `x ^ y`: x to the power y.
+///
+/// A negative real number to a fractional power is complex.
+fn power(x: &Atom, y: &Atom) -> Result<Atom> {
+    match (x.try_to_f64(), y.try_to_f64()) {
+        (Some(x), Some(y)) if x >= 0.0 || y.fract() == 0.0 => Ok(x.powf(y).into()),
+        _ => Ok(x.to_complex()?.powc(y.to_complex()?).into()),
+    }
+}
+
+/// `^. y`: the natural logarithm of y.
+///
+/// The logarithm of 0 is negative infinity, and of a negative number is
+/// complex.
+fn natural_log(y: &Atom) -> Result<Atom> {
+    match y.try_to_f64() {
+        Some(y) if y >= 0.0 => Ok(y.ln().into()),
+        _ => Ok(y.to_complex()?.ln().into()),
+    }
+}
+
+/// `x ^. y`: the base-x logarithm of y.
+fn logarithm(x: &Atom, y: &Atom) -> Result<Atom> {
+    match (x.try_to_f64(), y.try_to_f64()) {
+        // Base 2 logarithms give exact results for more common cases, such
+        // as `2 ^. 8`.
+        (Some(x), Some(y)) if x > 0.0 && y >= 0.0 => divide(&y.log2().into(), &x.log2().into()),
+        _ => divide(&natural_log(y)?, &natural_log(x)?),
+    }
+}
+
+/// Return the real finite value of an atom, or a domain error.
+fn finite_real(a: &Atom) -> Result<f64> {
+    match a.try_to_f64() {
+        Some(v) if v.is_finite() => Ok(v),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// `x +. y`: greatest common divisor, which is always non-negative.
+///
+/// On booleans this is the same as logical or.
+fn gcd(x: &Atom, y: &Atom) -> Result<Atom> {
+    let (mut a, mut b) = (finite_real(x)?.abs(), finite_real(y)?.abs());
+    while b != 0.0 {
+        (a, b) = (b, a % b);
+    }
+    Ok(a.into())
+}
+
+/// `x *. y`: least common multiple, with the sign of `x * y`.
+///
+/// On booleans this is the same as logical and.
+fn lcm(x: &Atom, y: &Atom) -> Result<Atom> {
+    let (a, b) = (finite_real(x)?, finite_real(y)?);
+    if a == 0.0 || b == 0.0 {
+        return Ok(Atom::zero());
+    }
+    let g = gcd(x, y)?.try_to_f64().expect("gcd is real");
+    Ok((a * (b / g)).into())
+}
+
+/// `q: y`: the prime factors of each atom of y, in ascending order, so that
+/// `q: 12` is `2 2 3`.
+fn prime_factors(y: &Noun) -> Result<Noun> {
+    monad_at_rank(y, Rank::Finite(0), |y| {
+        Ok(Noun::from(
+            factorize(y)?
+                .into_iter()
+                .flat_map(|(p, e)| std::iter::repeat_n(Atom::from(p as f64), e))
+                .collect::<Array>(),
+        ))
+    })
+}
+
+/// `x q: y`: the exponents in the prime factorization of each atom of y.
+///
+/// If x is a non-negative integer, the result is the exponents of the first x
+/// primes. If x is `_`, it's the exponents of all the primes up to the
+/// largest factor. If x is `__`, it's a table of the prime factors and their
+/// exponents.
+fn prime_exponents(x: &Noun, y: &Noun) -> Result<Noun> {
+    dyad_at_rank(x, y, (Rank::Finite(0), Rank::Finite(0)), |x, y| {
+        let x = x
+            .iter_atoms()
+            .next()
+            .and_then(Atom::try_to_f64)
+            .ok_or(Error::Domain)?;
+        let factors = factorize(y)?;
+        if x == f64::NEG_INFINITY {
+            let (primes, exponents): (Vec<Atom>, Vec<Atom>) = factors
+                .iter()
+                .map(|&(p, e)| (Atom::from(p as f64), Atom::from(e)))
+                .unzip();
+            let mut atoms = primes;
+            atoms.extend(exponents);
+            return Array::from_shape_vec(&[2, factors.len()], atoms).map(Noun::from);
+        }
+        // Only primes up to the largest factor can have non-zero exponents,
+        // and for a count of primes, only as many as are counted.
+        let largest = factors.last().map_or(1, |&(p, _)| p);
+        let (len, sieve_limit) = if x == f64::INFINITY {
+            (None, largest)
+        } else if x >= 0.0 && x.fract() == 0.0 && x <= crate::ARRAY_SIZE_LIMIT as f64 {
+            (Some(x as usize), largest.min(nth_prime_bound(x as u64)))
+        } else if x >= 0.0 && x.fract() == 0.0 {
+            return Err(Error::OutOfMemory);
+        } else {
+            return Err(Error::Domain);
+        };
+        if sieve_limit > crate::ARRAY_SIZE_LIMIT as u64 {
+            return Err(Error::OutOfMemory);
+        }
+        let primes = primes_up_to(sieve_limit as usize);
+        let len = len.unwrap_or(primes.len());
+        let mut exponents = vec![Atom::zero(); len];
+        for (i, p) in primes.iter().enumerate().take(len) {
+            if let Some(&(_, e)) = factors.iter().find(|(f, _)| f == p) {
+                exponents[i] = Atom::from(e);
+            }
+        }
+        Ok(Noun::from(Array::from(exponents)))
+    })
+}
+
+/// Return the prime factors of a positive integer atom, and the exponent of
+/// each, in ascending order of the factors.
+///
+/// Integers too large to be exact have no reliable factors, and are a domain
+/// error.
+fn factorize(y: &Noun) -> Result<Vec<(u64, usize)>> {
+    let y = y
+        .iter_atoms()
+        .next()
+        .ok_or(Error::Domain)
+        .and_then(integer)?;
+    if !(1.0..EXACT_INTEGER_LIMIT).contains(&y) {
+        return Err(Error::Domain);
+    }
+    let mut n = y as u64;
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        let mut e = 0;
+        while n.is_multiple_of(p) {
+            n /= p;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((p, e));
+        }
+        p += if p == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    Ok(factors)
+}
+
+/// An upper bound for the nth prime, counting 2 as the first.
+fn nth_prime_bound(n: u64) -> u64 {
+    if n < 6 {
+        11
+    } else {
+        let n = n as f64;
+        (n * (n.ln() + n.ln().ln())).ceil() as u64
+    }
+}
+
+/// The primes up to and including n, found by the sieve of Eratosthenes.
+fn primes_up_to(n: usize) -> Vec<u64> {
+    let mut composite = vec![false; n + 1];
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if !composite[i] {
+            primes.push(i as u64);
+            for c in (i * i..=n).step_by(i) {
+                composite[c] = true;
+            }
+        }
+    }
+    primes
+}
+
+/// `? y`: roll: a random integer from 0 up to but not including y; or if y
+/// is 0, a random number between 0 and 1.
+fn roll(y: &Atom) -> Result<Atom> {
+    let y = integer(y)?;
+    if y == 0.0 {
+        Ok(Atom::from(random::fraction()))
+    } else if y > 0.0 && y < EXACT_INTEGER_LIMIT {
+        Ok(Atom::from(random::below(y as u64) as f64))
     } else {
-        Ok(Atom::Complex(x.to_complex() * y.to_complex()))
+        Err(Error::Domain)
     }
 }
 
+/// `x ? y`: deal: x different integers chosen at random from `i. y`.
+fn deal(x: &Noun, y: &Noun) -> Result<Noun> {
+    dyad_at_rank(x, y, (Rank::Finite(0), Rank::Finite(0)), |x, y| {
+        let count = |n: &Noun| n.iter_atoms().next().ok_or(Error::Domain).and_then(integer);
+        let (x, y) = (count(x)?, count(y)?);
+        if x < 0.0 || x > y || y >= EXACT_INTEGER_LIMIT {
+            return Err(Error::Domain);
+        } else if x > crate::ARRAY_SIZE_LIMIT as f64 {
+            return Err(Error::OutOfMemory);
+        }
+        let (x, y) = (x as usize, y as u64);
+        let dealt = if x as u64 * 2 <= y {
+            // Mostly the numbers won't have been chosen already, so try again
+            // until there are enough.
+            let mut seen = HashSet::new();
+            let mut dealt = Vec::with_capacity(x);
+            while dealt.len() < x {
+                let n = random::below(y);
+                if seen.insert(n) {
+                    dealt.push(n);
+                }
+            }
+            dealt
+        } else {
+            // y is at most twice x, so it's small enough to shuffle the start
+            // of the whole list.
+            let mut all: Vec<u64> = (0..y).collect();
+            for i in 0..x {
+                let j = i + random::below(y - i as u64) as usize;
+                all.swap(i, j);
+            }
+            all.truncate(x);
+            all
+        };
+        Ok(Noun::from(
+            dealt
+                .into_iter()
+                .map(|n| Atom::from(n as f64))
+                .collect::<Array>(),
+        ))
+    })
+}
+
+/// `x = y`: 1 if the atoms are equal, otherwise 0.
+///
+/// Atoms of different types, such as a character and a number, are never
+/// equal.
+fn equal(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from((x == y) as usize))
+}
+
+/// `x -: y`: match: 1 if x and y have the same shape and atoms, otherwise 0.
+fn match_nouns(x: &Noun, y: &Noun) -> Result<Noun> {
+    Ok(Noun::from(Atom::from((x == y) as usize)))
+}
+
+/// `x ~: y`: 1 if the atoms are not equal, otherwise 0.
+fn not_equal(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from((x != y) as usize))
+}
+
+/// `%. y`: the inverse of a square matrix of real numbers.
+///
+/// The inverse of an atom is its reciprocal. Returns [Error::Domain] for a
+/// matrix that's singular or not square.
+fn matrix_inverse(y: &Noun) -> Result<Noun> {
+    let n = match y.dims() {
+        [] => return Dyad::Zero(divide).apply(&Noun::from(1.0), y),
+        &[rows, cols] if rows == cols => rows,
+        _ => return Err(Error::Domain),
+    };
+    let a = y
+        .iter_atoms()
+        .map(finite_real)
+        .collect::<Result<Vec<f64>>>()?;
+    let scale = a.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    // Fraction-free (Bareiss) Gauss-Jordan elimination on `[a | identity]`.
+    // On integer matrices every intermediate division is exact, which
+    // reduces `a` to its determinant times the identity, and the identity to
+    // the adjugate of `a`. So the inverse of an integer matrix is only rounded
+    // once, in the final division.
+    let mut rows: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let mut row = a[i * n..(i + 1) * n].to_vec();
+            row.extend((0..n).map(|j| (i == j) as usize as f64));
+            row
+        })
+        .collect();
+    let mut prev_pivot = 1.0;
+    for col in 0..n {
+        // Choose the largest available pivot for numerical stability on
+        // non-integer matrices.
+        let pivot_row = (col..n)
+            .max_by(|&i, &j| rows[i][col].abs().total_cmp(&rows[j][col].abs()))
+            .expect("at least one row");
+        // Entries at this step are determinants of minors of size `col + 1`.
+        let tolerance = scale.powi(col as i32 + 1) * n as f64 * f64::EPSILON;
+        if rows[pivot_row][col].abs() <= tolerance {
+            return Err(Error::Domain);
+        }
+        rows.swap(col, pivot_row);
+        let pivot_values = rows[col].clone();
+        let pivot = pivot_values[col];
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != col {
+                let factor = row[col];
+                for (v, p) in row.iter_mut().zip(&pivot_values) {
+                    *v = (pivot * *v - factor * p) / prev_pivot;
+                }
+            }
+        }
+        prev_pivot = pivot;
+    }
+    // Each row is now the determinant in the diagonal position, followed by
+    // a row of the adjugate.
+    let atoms = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row[n..].iter().map(move |v| Atom::from(v / row[i])))
+        .collect();
+    Array::from_shape_vec(&[n, n], atoms).map(Noun::Array)
+}
+
+/// Compare two real numbers, or two characters by their byte values.
+///
+/// Returns [Error::Domain] for other combinations, including a character and
+/// a number, or if either is NaN, which should never happen.
+fn compare(x: &Atom, y: &Atom) -> Result<Ordering> {
+    match (x, y) {
+        (Atom::Char(x), Atom::Char(y)) => Ok(x.cmp(y)),
+        _ => {
+            let (x, y) = (x.try_to_f64(), y.try_to_f64());
+            x.zip(y)
+                .and_then(|(x, y)| x.partial_cmp(&y))
+                .ok_or(Error::Domain)
+        }
+    }
+}
+
+/// `x < y`: less than.
+fn less(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from(compare(x, y)?.is_lt() as usize))
+}
+
+/// `x <: y`: less than or equal.
+fn less_or_equal(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from(compare(x, y)?.is_le() as usize))
+}
+
+/// `x > y`: larger than.
+fn larger(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from(compare(x, y)?.is_gt() as usize))
+}
+
+/// `x >: y`: larger than or equal.
+fn larger_or_equal(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(Atom::from(compare(x, y)?.is_ge() as usize))
+}
+
+/// `x <. y`: the lesser of x and y.
+fn lesser_of(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(if compare(x, y)?.is_le() { x } else { y }.clone())
+}
+
+/// `x >. y`: the larger of x and y.
+fn larger_of(x: &Atom, y: &Atom) -> Result<Atom> {
+    Ok(if compare(x, y)?.is_ge() { x } else { y }.clone())
+}
+
+/// `<. y`: the largest integer not more than y.
+fn floor(y: &Atom) -> Result<Atom> {
+    Ok(y.try_to_f64().ok_or(Error::Domain)?.floor().into())
+}
+
+/// `>. y`: the smallest integer not less than y.
+fn ceiling(y: &Atom) -> Result<Atom> {
+    Ok(y.try_to_f64().ok_or(Error::Domain)?.ceil().into())
+}
+
 fn not(y: &Atom) -> Result<Atom> {
     let y = y.try_to_f64().ok_or(Error::Domain)?;
     if y == 0.0 {
@@ -270,7 +870,7 @@ fn integers(y: &Noun) -> Result<Noun> {
                 if y > crate::ARRAY_SIZE_LIMIT {
                     return Err(Error::OutOfMemory);
                 }
-                Ok(Noun::Array(Array::from((0..y).into_iter().map(Atom::from))))
+                Ok(Noun::Array(Array::from((0..y).map(Atom::from))))
             } else {
                 Err(Error::Domain)
             }
@@ -279,3 +879,729 @@ fn integers(y: &Noun) -> Result<Noun> {
         _ => Err(Error::Unimplemented("integers from list".into())),
     }
 }
+
+/// `i: y`: the integers from `-y` to `y`, or in descending order if y is
+/// negative.
+///
+/// The stepped form, with a complex y, is not implemented yet.
+fn steps(y: &Noun) -> Result<Noun> {
+    match y {
+        Noun::Atom(Atom::Complex(c)) if c.im != 0.0 => {
+            Err(Error::Unimplemented("i: with complex steps".into()))
+        }
+        Noun::Atom(a) => {
+            let n = integer(a)?;
+            if n.abs() > (crate::ARRAY_SIZE_LIMIT / 2) as f64 {
+                return Err(Error::OutOfMemory);
+            }
+            let n = n as i64;
+            let sign = if n < 0 { -1 } else { 1 };
+            Ok(Noun::Array(Array::from(
+                (-n.abs()..=n.abs()).map(|i| Atom::from((i * sign) as f64)),
+            )))
+        }
+        _ => Err(Error::Unimplemented("steps from list".into())),
+    }
+}
+
+/// `x $ y`: reshape the items of y into an array whose leading axes are x.
+///
+/// The items of y are repeated cyclically, or truncated, to fill the new shape.
+fn reshape(x: &Noun, y: &Noun) -> Result<Noun> {
+    let mut shape = Vec::new();
+    for a in x.iter_atoms() {
+        let d = a.try_to_f64().ok_or(Error::Domain)?;
+        if d < 0.0 || d.fract() != 0.0 {
+            return Err(Error::Domain);
+        }
+        shape.push(d as usize);
+    }
+    if shape.is_empty() {
+        // Reshaping to an empty shape gives the first item, as an atom.
+        return Ok(Noun::Atom(
+            y.iter_atoms().next().cloned().unwrap_or_else(Atom::zero),
+        ));
+    }
+    match y {
+        Noun::Atom(a) => Array::from_vec(vec![a.clone()]).reshape(&shape),
+        Noun::Array(a) => {
+            shape.extend_from_slice(&a.dims()[1..]);
+            a.reshape(&shape)
+        }
+    }
+    .map(Noun::Array)
+}
+
+/// `, y`: a list of all the atoms of y.
+fn ravel(y: &Noun) -> Result<Noun> {
+    Ok(Noun::Array(y.iter_atoms().cloned().collect()))
+}
+
+/// `x , y`: a list of the items of x followed by the items of y.
+///
+/// An atom is repeated to the shape of an item of the other argument. An
+/// argument of lower rank than the other is treated as a single item. Items of
+/// different shapes are padded with fill.
+fn append(x: &Noun, y: &Noun) -> Result<Noun> {
+    let rank = x.dims().len().max(y.dims().len()).max(1);
+    let items = |n: &Noun, other: &Noun| -> Result<Vec<Noun>> {
+        Ok(match n {
+            Noun::Atom(_) if other.dims().len() == rank => {
+                vec![reshape(&shape_list(&other.dims()[1..]), n)?]
+            }
+            Noun::Array(a) if a.dims().len() == rank => a.iter_items().collect(),
+            _ => vec![n.clone()],
+        })
+    };
+    let mut all = items(x, y)?;
+    all.extend(items(y, x)?);
+    Noun::assemble(&[all.len()], &all)
+}
+
+/// `,: y`: a list of one item, y.
+fn itemize(y: &Noun) -> Result<Noun> {
+    Noun::assemble(&[1], std::slice::from_ref(y))
+}
+
+/// `x ,: y`: laminate: a list of two items, x and y.
+///
+/// An atom is repeated to the shape of the other argument.
+fn laminate(x: &Noun, y: &Noun) -> Result<Noun> {
+    let x = match x {
+        Noun::Atom(_) => reshape(&shape_list(y.dims()), x)?,
+        _ => x.clone(),
+    };
+    let y = match y {
+        Noun::Atom(_) => reshape(&shape_list(x.dims()), y)?,
+        _ => y.clone(),
+    };
+    append(&itemize(&x)?, &itemize(&y)?)
+}
+
+/// Build a shape list, as a noun, from axis lengths.
+fn shape_list(dims: &[usize]) -> Noun {
+    Noun::Array(dims.iter().copied().map(Atom::from).collect())
+}
+
+/// `": y`: format y as characters, the same as it would be displayed.
+///
+/// Results that display on several lines are formatted as a table of
+/// characters. Since characters are single bytes, boxes are drawn with ASCII
+/// characters like `+-+`.
+fn default_format(y: &Noun) -> Result<Noun> {
+    let is_char = match y {
+        Noun::Atom(a) => a.is_char(),
+        Noun::Array(a) => a.is_char(),
+    };
+    if is_char {
+        return Ok(y.clone());
+    }
+    // The alternate flag selects ASCII boxes.
+    let s = format!("{:#}", y);
+    let rows: Vec<Noun> = s.lines().map(Noun::char_list).collect();
+    match rows.len() {
+        0 => Ok(Noun::char_list("")),
+        1 => Ok(rows.into_iter().next().unwrap()),
+        n => Noun::assemble(&[n], &rows),
+    }
+}
+
+/// `x # y`: copy each item of y the number of times given by the
+/// corresponding atom of x.
+///
+/// If x is an atom, every item is copied that many times. This is commonly
+/// used with a boolean x to select items from y.
+fn copy(x: &Noun, y: &Noun) -> Result<Noun> {
+    let items = y.items();
+    let counts: Vec<usize> = x
+        .iter_atoms()
+        .map(|a| match a.try_to_f64() {
+            Some(c) if c >= 0.0 && c.fract() == 0.0 => Ok(c as usize),
+            _ => Err(Error::Domain),
+        })
+        .collect::<Result<_>>()?;
+    let counts = match (x, counts.as_slice()) {
+        (Noun::Atom(_), &[c]) => vec![c; items.len()],
+        (Noun::Array(ax), _) if ax.dims().len() > 1 => {
+            return Err(Error::Unimplemented("copy with a table on the left".into()))
+        }
+        _ if matches!(y, Noun::Atom(_)) => vec![checked_sum(&counts)?],
+        _ if counts.len() == items.len() => counts,
+        _ => return Err(Error::Length),
+    };
+    let total = checked_sum(&counts)?;
+    let mut result = Vec::with_capacity(total);
+    for (item, &count) in items.iter().zip(&counts) {
+        result.extend(std::iter::repeat_n(item, count).cloned());
+    }
+    Noun::assemble(&[result.len()], &result)
+}
+
+/// `x #: y`: represent each atom of y in the number system with radixes x.
+///
+/// For example `24 60 60 #: 3661` converts seconds to hours, minutes, and
+/// seconds. Each atom of y gives a list of digits, one for each radix. Any
+/// overflow beyond the leading radix is discarded, unless the leading radix is
+/// 0, in which case that digit takes all the remainder.
+///
+/// All the arguments must be integers.
+fn antibase(x: &Noun, y: &Noun) -> Result<Noun> {
+    let radixes = x.iter_atoms().map(integer).collect::<Result<Vec<f64>>>()?;
+    if x.dims().len() > 1 {
+        return Err(Error::Unimplemented(
+            "antibase with a table of radixes".into(),
+        ));
+    }
+    let results = y
+        .iter_atoms()
+        .map(|a| {
+            let mut y = integer(a)?;
+            let mut digits = vec![Atom::zero(); radixes.len()];
+            for (digit, &radix) in digits.iter_mut().zip(&radixes).rev() {
+                if radix == 0.0 {
+                    *digit = y.into();
+                    y = 0.0;
+                } else {
+                    let d = y - radix * (y / radix).floor();
+                    *digit = d.into();
+                    y = (y - d) / radix;
+                }
+            }
+            Ok(Noun::from(Array::from_shape_vec(x.dims(), digits)?))
+        })
+        .collect::<Result<Vec<Noun>>>()?;
+    Noun::assemble(y.dims(), &results)
+}
+
+/// `#. y`: the number whose binary digits are the list y, such as 11 for
+/// `1 0 1 1`. A table gives the number for each row.
+///
+/// This is `2 #. y`, but lists of bits are converted with bit operations.
+fn base_2(y: &Noun) -> Result<Noun> {
+    monad_at_rank(y, Rank::Finite(1), |y| match bits_value(y) {
+        Some(v) => Ok(Noun::from(v as f64)),
+        None => base(&Noun::from(2.0), y),
+    })
+}
+
+/// If y is a list of at most 53 zeros and ones, return the number they
+/// represent in binary.
+fn bits_value(y: &Noun) -> Option<u64> {
+    if y.iter_atoms().count() > 53 {
+        return None;
+    }
+    y.iter_atoms()
+        .try_fold(0u64, |acc, a| match a.try_to_f64() {
+            Some(b) if b == 0.0 || b == 1.0 => Some((acc << 1) | b as u64),
+            _ => None,
+        })
+}
+
+/// `x #. y`: the number represented by the digits y in the number system
+/// whose radixes are x, such as `24 60 60 #. 1 1 1` for 3661.
+///
+/// This applies to lists in x and y. An atom x is the radix for every digit;
+/// otherwise x must be as long as y.
+fn base(x: &Noun, y: &Noun) -> Result<Noun> {
+    let ranks = (Rank::Finite(1), Rank::Finite(1));
+    dyad_at_rank(x, y, ranks, |x, y| {
+        let digits = y
+            .iter_atoms()
+            .map(finite_real)
+            .collect::<Result<Vec<f64>>>()?;
+        let mut radixes = x
+            .iter_atoms()
+            .map(finite_real)
+            .collect::<Result<Vec<f64>>>()?;
+        if x.dims().is_empty() {
+            radixes = vec![radixes[0]; digits.len()];
+        } else if radixes.len() != digits.len() {
+            return Err(Error::Length);
+        }
+        // The radix of the first digit has no effect.
+        let value = digits
+            .iter()
+            .zip(&radixes)
+            .fold(0.0, |acc, (&digit, &radix)| acc * radix + digit);
+        Ok(Noun::from(value))
+    })
+}
+
+/// `#: y`: the binary digits of y, with as many digits as are needed for the
+/// largest magnitude in y, so that `#: 11` is `1 0 1 1`.
+///
+/// This is `x #: y` with x a list of 2s, but non-negative numbers that are
+/// exactly represented are converted with bit operations.
+fn antibase_2(y: &Noun) -> Result<Noun> {
+    let values = y.iter_atoms().map(integer).collect::<Result<Vec<f64>>>()?;
+    let max = values.iter().fold(1.0, |max: f64, v| max.max(v.abs()));
+    let len = if max < EXACT_INTEGER_LIMIT {
+        (u64::BITS - (max as u64).leading_zeros()) as usize
+    } else {
+        max.log2().floor() as usize + 1
+    };
+    if max >= EXACT_INTEGER_LIMIT || values.iter().any(|&v| v < 0.0) {
+        return antibase(&Noun::from(vec![Atom::from(2.0); len]), y);
+    }
+    let digits = values
+        .iter()
+        .flat_map(|&v| {
+            (0..len)
+                .rev()
+                .map(move |i| Atom::from(((v as u64 >> i) & 1) as f64))
+        })
+        .collect();
+    let mut shape = y.dims().to_vec();
+    shape.push(len);
+    Array::from_shape_vec(&shape, digits).map(Noun::from)
+}
+
+/// Return the value of an atom that must be a finite integer.
+fn integer(a: &Atom) -> Result<f64> {
+    match finite_real(a)? {
+        v if v.fract() == 0.0 => Ok(v),
+        _ => Err(Error::Domain),
+    }
+}
+
+/// Add up counts of atoms, returning [Error::OutOfMemory] if the total is
+/// too large.
+fn checked_sum(counts: &[usize]) -> Result<usize> {
+    counts
+        .iter()
+        .try_fold(0usize, |acc, &c| acc.checked_add(c))
+        .filter(|&total| total <= crate::ARRAY_SIZE_LIMIT)
+        .ok_or(Error::OutOfMemory)
+}
+
+/// `~: y`: a boolean list marking the first occurrence of each distinct item of y.
+fn nub_sieve(y: &Noun) -> Result<Noun> {
+    let items = y.items();
+    Ok(Noun::Array(
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| Atom::from(!items[..i].contains(item) as usize))
+            .collect(),
+    ))
+}
+
+/// `~. y`: nub: the distinct items of y, in the order they first occur.
+fn nub(y: &Noun) -> Result<Noun> {
+    let mut nub: Vec<Noun> = Vec::new();
+    for item in y.items() {
+        if !nub.contains(&item) {
+            nub.push(item);
+        }
+    }
+    Noun::assemble(&[nub.len()], &nub)
+}
+
+/// Look up each cell of y that's the shape of an item of x, returning the
+/// frame of those cells and the index of the first matching item of x for each
+/// one, if any.
+fn find_cells<'y>(x: &Noun, y: &'y Noun) -> Result<(&'y [usize], Vec<Option<usize>>)> {
+    let items = x.items();
+    let item_rank = x.dims().len().saturating_sub(1);
+    if y.dims().len() < item_rank {
+        return Err(Error::Rank);
+    }
+    let (frame, cells) = y.cells(item_rank);
+    let found = cells
+        .iter()
+        .map(|cell| items.iter().position(|item| item == cell))
+        .collect();
+    Ok((frame, found))
+}
+
+/// `x i. y`: index of: for each cell of y that's the shape of an item of x,
+/// the index of the first matching item of x, or `# x` if there is none.
+fn index_of(x: &Noun, y: &Noun) -> Result<Noun> {
+    let n = x.items().len();
+    let (frame, found) = find_cells(x, y)?;
+    let atoms = found
+        .into_iter()
+        .map(|i| Atom::from(i.unwrap_or(n)))
+        .collect();
+    Array::from_shape_vec(frame, atoms).map(Noun::from)
+}
+
+/// `x e. y`: member: 1 for each cell of x that's the shape of an item of y
+/// and matches one of them, otherwise 0.
+fn member(x: &Noun, y: &Noun) -> Result<Noun> {
+    let (frame, found) = find_cells(y, x)?;
+    let atoms = found
+        .into_iter()
+        .map(|i| Atom::from(i.is_some() as usize))
+        .collect();
+    Array::from_shape_vec(frame, atoms).map(Noun::from)
+}
+
+/// `= y`: self-classify: a boolean table with one row for each unique item
+/// of y, marking where it occurs in y.
+fn self_classify(y: &Noun) -> Result<Noun> {
+    let items = y.items();
+    let mut nub: Vec<&Noun> = Vec::new();
+    for item in &items {
+        if !nub.contains(&item) {
+            nub.push(item);
+        }
+    }
+    let atoms = nub
+        .iter()
+        .flat_map(|n| {
+            items
+                .iter()
+                .map(move |item| Atom::from((item == *n) as usize))
+        })
+        .collect();
+    Array::from_shape_vec(&[nub.len(), items.len()], atoms).map(Noun::Array)
+}
+
+/// `[ y` and `] y`: y itself.
+fn same(y: &Noun) -> Result<Noun> {
+    Ok(y.clone())
+}
+
+/// `x [ y`: x.
+fn left(x: &Noun, _y: &Noun) -> Result<Noun> {
+    Ok(x.clone())
+}
+
+/// `x ] y`: y.
+fn right(_x: &Noun, y: &Noun) -> Result<Noun> {
+    Ok(y.clone())
+}
+
+/// `|. y`: the items of y in reverse order.
+fn reverse(y: &Noun) -> Result<Noun> {
+    match y {
+        Noun::Atom(_) => Ok(y.clone()),
+        Noun::Array(a) => {
+            let items: Vec<Noun> = a.iter_items().rev().collect();
+            Noun::assemble(&[items.len()], &items)
+        }
+    }
+}
+
+/// `x |. y`: rotate: move the first x items of y to the end, or if x is
+/// negative, the last -x items to the start.
+///
+/// If x is a list, its successive atoms rotate successive axes of y, so
+/// `1 2 |. y` rotates the rows of a table by 1 and each row by 2. An atom y is
+/// treated as a list of one item.
+fn rotate(x: &Noun, y: &Noun) -> Result<Noun> {
+    if x.dims().len() > 1 {
+        return Err(Error::Rank);
+    }
+    let amounts = x
+        .iter_atoms()
+        .map(|a| integer(a).map(|a| a as i64))
+        .collect::<Result<Vec<i64>>>()?;
+    let y = match y {
+        Noun::Atom(a) => Noun::from(vec![a.clone()]),
+        Noun::Array(_) => y.clone(),
+    };
+    if amounts.len() > y.dims().len() {
+        return Err(Error::Length);
+    }
+    rotate_axes(&amounts, &y)
+}
+
+/// Rotate the items of y by the first amount, and the axes within the items by
+/// the following amounts.
+fn rotate_axes(amounts: &[i64], y: &Noun) -> Result<Noun> {
+    let Some((&amount, rest)) = amounts.split_first() else {
+        return Ok(y.clone());
+    };
+    let items = y.items();
+    if items.is_empty() {
+        return Ok(y.clone());
+    }
+    let shift = amount.rem_euclid(items.len() as i64) as usize;
+    let rotated = items[shift..]
+        .iter()
+        .chain(&items[..shift])
+        .map(|item| rotate_axes(rest, item))
+        .collect::<Result<Vec<Noun>>>()?;
+    Noun::assemble(&[rotated.len()], &rotated)
+}
+
+/// `|: y`: transpose: y with the order of its axes reversed.
+fn transpose(y: &Noun) -> Result<Noun> {
+    match y {
+        Noun::Atom(_) => Ok(y.clone()),
+        Noun::Array(a) => {
+            let axes: Vec<usize> = (0..a.dims().len()).rev().collect();
+            Ok(Noun::Array(a.permuted_axes(&axes)))
+        }
+    }
+}
+
+/// `x |: y`: y with the axes listed in x moved to the end, in that order.
+///
+/// If x lists every axis, it's a permutation giving the axis of y that becomes
+/// each axis of the result, so `1 0 |: y` transposes a table. Negative axis
+/// numbers count back from the last axis. Returns [Error::Domain] if x is
+/// not a list of distinct axes of y.
+fn transpose_axes(x: &Noun, y: &Noun) -> Result<Noun> {
+    if x.dims().len() > 1 {
+        return Err(Error::Rank);
+    }
+    let rank = y.dims().len();
+    let mut moved = Vec::new();
+    for a in x.iter_atoms() {
+        let axis = a.to_index(rank).map_err(|_| Error::Domain)?;
+        if moved.contains(&axis) {
+            return Err(Error::Domain);
+        }
+        moved.push(axis);
+    }
+    let mut axes: Vec<usize> = (0..rank).filter(|i| !moved.contains(i)).collect();
+    axes.extend(moved);
+    match y {
+        Noun::Atom(_) => Ok(y.clone()),
+        Noun::Array(a) => Ok(Noun::Array(a.permuted_axes(&axes))),
+    }
+}
+
+/// `x { y`: the items of y selected by the indexes in x.
+///
+/// The result has the shape of x, followed by the shape of an item of y.
+/// Negative indexes count back from the end.
+fn from(x: &Noun, y: &Noun) -> Result<Noun> {
+    from_with_fill(x, y, None)
+}
+
+/// `x {!.f y`: like `x { y`, but an index that's out of range selects an item
+/// made entirely of the fill atom, rather than giving [Error::Length].
+pub(crate) fn from_with_fill(x: &Noun, y: &Noun, fill: Option<&Atom>) -> Result<Noun> {
+    let items = y.items();
+    let item_shape = y.dims().get(1..).unwrap_or_default();
+    let selected = x
+        .iter_atoms()
+        .map(|i| match (i, i.to_index(items.len()), fill) {
+            (Atom::Boxed(_), _, _) => Err(Error::Unimplemented("from with boxed indexes".into())),
+            (_, Ok(i), _) => Ok(items[i].clone()),
+            (_, Err(Error::Length), Some(fill)) if item_shape.is_empty() => {
+                Ok(Noun::from(fill.clone()))
+            }
+            (_, Err(Error::Length), Some(fill)) => {
+                Array::from_shape_vec(item_shape, vec![fill.clone(); item_shape.iter().product()])
+                    .map(Noun::from)
+            }
+            (_, Err(err), _) => Err(err),
+        })
+        .collect::<Result<Vec<Noun>>>()?;
+    Noun::assemble(x.dims(), &selected)
+}
+
+/// Return the indexes of the items of y in ascending order of the items.
+///
+/// Items are compared atom by atom. The sort is stable, so equal items keep
+/// their original order.
+fn grade(y: &Noun) -> Result<Vec<usize>> {
+    let items: Vec<Vec<Atom>> = y
+        .items()
+        .iter()
+        .map(|item| item.iter_atoms().cloned().collect())
+        .collect();
+    let mut error = None;
+    let mut indexes: Vec<usize> = (0..items.len()).collect();
+    indexes.sort_by(|&a, &b| {
+        for (x, y) in items[a].iter().zip(&items[b]) {
+            match compare(x, y) {
+                Ok(Ordering::Equal) => (),
+                Ok(ord) => return ord,
+                Err(err) => {
+                    error.get_or_insert(err);
+                    return Ordering::Equal;
+                }
+            }
+        }
+        Ordering::Equal
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(indexes),
+    }
+}
+
+/// `/: y`: grade up: the indexes that would sort the items of y into
+/// ascending order.
+fn grade_up(y: &Noun) -> Result<Noun> {
+    Ok(Noun::from(
+        grade(y)?.into_iter().map(Atom::from).collect::<Vec<Atom>>(),
+    ))
+}
+
+/// `x /: y`: sort up: the items of x, in the ascending order of the items of
+/// y.
+fn sort_up(x: &Noun, y: &Noun) -> Result<Noun> {
+    let items = x.items();
+    if items.len() != y.items().len() {
+        return Err(Error::Length);
+    }
+    let sorted: Vec<Noun> = grade(y)?.into_iter().map(|i| items[i].clone()).collect();
+    Noun::assemble(&[sorted.len()], &sorted)
+}
+
+/// `{. y`: the first item of y.
+///
+/// The head of an atom is the atom itself. If y is empty, the result is an
+/// item made of the fill for the type of y, such as zeros or spaces.
+fn head(y: &Noun) -> Result<Noun> {
+    let dims = y.dims();
+    match dims.split_first() {
+        None => Ok(y.clone()),
+        Some((0, item_shape)) => {
+            let n = item_shape.iter().product();
+            Array::from_shape_vec(item_shape, vec![y.fill(); n])
+                .map(|a| Noun::from(a.with_fill(y.fill())))
+        }
+        Some(_) => Ok(y.items().swap_remove(0)),
+    }
+}
+
+/// `{: y`: the last item of y.
+///
+/// As for `{.`, the tail of an atom is the atom itself, and if y is empty the
+/// result is an item of fill atoms.
+fn tail(y: &Noun) -> Result<Noun> {
+    match y.dims().first() {
+        Some(&n) if n > 0 => Ok(y.items().swap_remove(n - 1)),
+        _ => head(y),
+    }
+}
+
+/// `}. y`: all the items of y except the first.
+///
+/// Beheading an empty array or a single item gives an empty array. An atom has
+/// no items to remove, so is a rank error.
+fn behead(y: &Noun) -> Result<Noun> {
+    let dims = y.dims();
+    let (&n, item_shape) = dims.split_first().ok_or(Error::Rank)?;
+    let mut shape = vec![n.saturating_sub(1)];
+    shape.extend_from_slice(item_shape);
+    let item_len = item_shape.iter().product::<usize>();
+    let atoms = y.iter_atoms().skip(item_len).cloned().collect();
+    Array::from_shape_vec(&shape, atoms).map(|a| Noun::Array(a.with_fill(y.fill())))
+}
+
+/// `}: y`: all the items of y except the last.
+///
+/// Curtailing a single item gives an empty array. An atom has no items to
+/// remove, so is a rank error.
+fn curtail(y: &Noun) -> Result<Noun> {
+    let dims = y.dims();
+    let (&n, item_shape) = dims.split_first().ok_or(Error::Rank)?;
+    let mut shape = vec![n.saturating_sub(1)];
+    shape.extend_from_slice(item_shape);
+    let len = shape.iter().product();
+    let atoms = y.iter_atoms().take(len).cloned().collect();
+    Array::from_shape_vec(&shape, atoms).map(|a| Noun::Array(a.with_fill(y.fill())))
+}
+
+/// `;: y`: word formation: the words of the sentence in the string y, each
+/// in a box.
+fn words(y: &Noun) -> Result<Noun> {
+    if y.dims().len() > 1 {
+        return Err(Error::Unimplemented("words of a table of sentences".into()));
+    }
+    let text = y
+        .iter_atoms()
+        .map(|a| match a {
+            Atom::Char(c) => Ok(*c as char),
+            _ => Err(Error::Domain),
+        })
+        .collect::<Result<String>>()?;
+    Ok(Noun::from(
+        crate::scan::word_texts(&text)?
+            .into_iter()
+            .map(|word| Atom::Boxed(Box::new(Noun::char_list(word))))
+            .collect::<Array>(),
+    ))
+}
+
+/// `x ": y`: format numbers in y with the width and decimal places given by x.
+///
+/// x is `w.d`: each number is formatted with `d` digits after the decimal
+/// point, right-justified in a field of `w` characters, or left-justified if
+/// `w` is negative. Numbers too wide for the field are shown as `*`s. If `w` is
+/// 0, each number takes only the space it needs, separated by a space.
+///
+/// Each row of y becomes one row of characters.
+fn format(x: &Noun, y: &Noun) -> Result<Noun> {
+    let spec = match x {
+        Noun::Atom(a) => a.try_to_f64().ok_or(Error::Domain)?,
+        Noun::Array(_) => return Err(Error::Unimplemented("format with a list of widths".into())),
+    };
+    let width = spec.abs().trunc() as usize;
+    if width
+        .checked_mul(y.iter_atoms().count())
+        .is_none_or(|len| len > crate::ARRAY_SIZE_LIMIT)
+    {
+        return Err(Error::OutOfMemory);
+    }
+    let decimals = (spec.abs().fract() * 10.0).round() as usize;
+    let left_justify = spec < 0.0;
+    let row_len = y.dims().last().copied().unwrap_or(1);
+    let fields = y
+        .iter_atoms()
+        .map(|a| {
+            let v = a.try_to_f64().ok_or(Error::Domain)?;
+            let s = if v == f64::INFINITY {
+                "_".to_owned()
+            } else if v == f64::NEG_INFINITY {
+                "__".to_owned()
+            } else {
+                let s = format!("{:.*}", decimals, v);
+                // Avoid showing negative zero, after rounding.
+                if s.trim_start_matches(['-', '0', '.']).is_empty() {
+                    s.trim_start_matches('-').to_owned()
+                } else {
+                    s.replace('-', "_")
+                }
+            };
+            Ok(if width == 0 {
+                s
+            } else if s.len() > width {
+                "*".repeat(width)
+            } else if left_justify {
+                format!("{:<w$}", s, w = width)
+            } else {
+                format!("{:>w$}", s, w = width)
+            })
+        })
+        .collect::<Result<Vec<String>>>()?;
+    let separator = if width == 0 { " " } else { "" };
+    let rows: Vec<String> = fields
+        .chunks(row_len.max(1))
+        .map(|row| row.join(separator))
+        .collect();
+    if y.dims().len() <= 1 {
+        return Ok(Noun::char_list(rows.first().map_or("", |r| r.as_str())));
+    }
+    let row_width = rows.iter().map(String::len).max().unwrap_or(0);
+    let mut shape = y.dims().to_vec();
+    *shape.last_mut().unwrap() = row_width;
+    let atoms = rows
+        .iter()
+        .flat_map(|row| format!("{:<w$}", row, w = row_width).into_bytes())
+        .map(Atom::Char)
+        .collect();
+    Ok(Array::from_shape_vec(&shape, atoms)?.into())
+}
+
+/// `< y`: box y, making an atom that contains it.
+fn box_noun(y: &Noun) -> Result<Noun> {
+    Ok(Noun::Atom(Atom::Boxed(Box::new(y.clone()))))
+}
+
+/// `> y`: open: the contents of a box, or an atom that's not a box.
+fn open(y: &Atom) -> Result<Noun> {
+    match y {
+        Atom::Boxed(contents) => Ok((**contents).clone()),
+        other => Ok(Noun::Atom(other.clone())),
+    }
+}
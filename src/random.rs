@@ -0,0 +1,125 @@
+// Copyright 2022 Martin Pool
+
+//! Random numbers, for `?`.
+//!
+//! The generator is SplitMix64: it's small and fast, and fine for games and
+//! simulations but not for cryptography. The sequences don't match J's.
+//!
+//! Each session has its own generator, which starts from a seed taken from
+//! the clock, and which can be reseeded to give reproducible results. While
+//! a session is evaluating a sentence, `?` draws from its generator.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A stream of random numbers.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: Cell<u64>,
+}
+
+impl Default for Random {
+    /// A generator seeded from the clock.
+    fn default() -> Random {
+        Random {
+            state: Cell::new(clock_seed()),
+        }
+    }
+}
+
+impl Random {
+    /// Restart the generator from the given seed, so that it gives the same
+    /// sequence each time.
+    pub fn seed(&self, seed: u64) {
+        self.state.set(seed);
+    }
+
+    fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        self.state.set(state);
+        let z = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A random integer from 0 up to but not including n.
+    pub fn below(&self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// A random number between 0 and 1, excluding both.
+    pub fn fraction(&self) -> f64 {
+        loop {
+            let f = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+            if f > 0.0 {
+                return f;
+            }
+        }
+    }
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+thread_local! {
+    /// The generator of the session that's evaluating a sentence on this
+    /// thread, or otherwise one for verbs applied outside any session.
+    static CURRENT: RefCell<Rc<Random>> = RefCell::new(Rc::default());
+}
+
+/// Call `f` with `?` drawing from `random`, and then go back to the previous
+/// generator, even if `f` panics.
+pub(crate) fn with_random<R>(random: &Rc<Random>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(Some(CURRENT.with(|c| c.replace(random.clone()))));
+    f()
+}
+
+/// Puts back the generator that was in use before [with_random] when
+/// dropped.
+struct Restore(Option<Rc<Random>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CURRENT.with(|c| *c.borrow_mut() = previous);
+        }
+    }
+}
+
+/// The generator of the session that's evaluating a sentence.
+pub(crate) fn current() -> Rc<Random> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// A random integer from 0 up to but not including n, from the current
+/// generator.
+pub(crate) fn below(n: u64) -> u64 {
+    current().below(n)
+}
+
+/// A random number between 0 and 1, excluding both, from the current
+/// generator.
+pub(crate) fn fraction() -> f64 {
+    current().fraction()
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::AssertUnwindSafe;
+
+    use super::*;
+
+    #[test]
+    fn generator_is_restored_after_a_panic() {
+        let before = current();
+        let random = Rc::new(Random::default());
+        let result =
+            std::panic::catch_unwind(AssertUnwindSafe(|| with_random(&random, || panic!("oops"))));
+        assert!(result.is_err());
+        assert!(Rc::ptr_eq(&current(), &before));
+    }
+}
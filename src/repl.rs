@@ -2,25 +2,75 @@
 
 //! Read-eval-print UI.
 
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bstr::BStr;
+use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
-use rustyline::Editor;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
 
+use crate::adverb::ADVERBS;
+use crate::conjunction::CONJUNCTIONS;
 use crate::eval::Session;
+use crate::primitive::PRIMITIVES;
 
 const PROMPT: &str = "   ";
 
+/// A line starting with this shows how the rest of the line is parsed.
+const TREE_DIRECTIVE: &str = ")tree";
+
+/// Options controlling the interactive session.
+#[derive(Debug, Default)]
+pub struct ReplOptions {
+    /// Truncate results that are taller than the terminal, showing how many
+    /// rows were left out.
+    pub truncate_rows: bool,
+    /// Log each verb application to stderr.
+    pub trace: bool,
+    /// A J script to evaluate before the first prompt, such as a profile of
+    /// the user's own definitions.
+    pub profile: Option<PathBuf>,
+}
+
 /// Read and evaluate input from stdin until stopped by ^c or ^d.
-pub fn repl() {
-    let mut rl = Editor::<()>::new();
+pub fn repl(options: &ReplOptions) {
+    let mut rl = Editor::<ReplHelper>::new();
+    rl.set_helper(Some(ReplHelper::default()));
     // TODO: Put the window width into the session output width?
     let mut session = Session::new();
+    session.set_trace(options.trace);
+    if let Some(profile) = &options.profile {
+        for message in load_profile(&mut session, profile) {
+            eprintln!("{}", message);
+        }
+    }
     loop {
+        if let Some(helper) = rl.helper_mut() {
+            helper.defined_names = session.defined_names();
+        }
         match rl.readline(PROMPT) {
             Ok(line) => {
                 rl.add_history_entry(line.as_str());
+                if let Some(sentence) = line.strip_prefix(TREE_DIRECTIVE) {
+                    for step in session.explain_parse(sentence) {
+                        println!("{}", step);
+                    }
+                    continue;
+                }
                 let output = session.eval_text(&line);
                 if !output.is_empty() {
-                    println!("{}", output);
+                    match rl.dimensions() {
+                        // Leave room for the input line and the next prompt.
+                        Some((_cols, rows)) if options.truncate_rows => {
+                            println!("{}", truncate_rows(&output, rows.saturating_sub(2)))
+                        }
+                        _ => println!("{}", output),
+                    }
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -38,3 +88,204 @@ pub fn repl() {
         }
     }
 }
+
+/// Evaluate each line of a profile script in the session, so that the names it
+/// defines can be used interactively.
+///
+/// Returns a message for each line that failed, or for a file that can't be
+/// read. Neither stops the REPL from starting, so the messages are only shown.
+pub fn load_profile(session: &mut Session, path: &Path) -> Vec<String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => return vec![format!("{}: {}", path.display(), err)],
+    };
+    let mut messages = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        for result in session.eval_all(line) {
+            if let Err(err) = result {
+                messages.push(format!("{}:{}: error: {:?}", path.display(), i + 1, err));
+            }
+        }
+    }
+    messages
+}
+
+/// If the output has more than `max_rows` lines, cut it short with a final
+/// line saying how many were left out.
+fn truncate_rows(output: &str, max_rows: usize) -> Cow<'_, str> {
+    let total = output.lines().count();
+    if total <= max_rows {
+        return output.into();
+    }
+    let keep = max_rows.saturating_sub(1);
+    let mut s: String = output.lines().take(keep).flat_map(|l| [l, "\n"]).collect();
+    s.push_str(&format!("... {} more rows", total - keep));
+    s.into()
+}
+
+/// Hooks into rustyline to complete the names of primitives and defined
+/// verbs.
+#[derive(Default)]
+struct ReplHelper {
+    /// The names of verbs defined in the session, as of the last prompt.
+    defined_names: Vec<String>,
+}
+
+impl Helper for ReplHelper {}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(complete_name(line, pos, &self.defined_names))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+/// The names of all primitive verbs, adverbs, and conjunctions.
+fn primitive_names() -> impl Iterator<Item = &'static BStr> {
+    PRIMITIVES
+        .iter()
+        .map(|p| p.name())
+        .chain(ADVERBS.iter().map(|a| a.name()))
+        .chain(CONJUNCTIONS.iter().map(|c| c.name()))
+}
+
+/// Find the names of primitives, adverbs, conjunctions, and verbs defined in
+/// the session, that could complete the text before `pos`.
+///
+/// Returns the position where the completed name starts, and the possible
+/// names. The longest partial name that matches anything is completed, so
+/// that after `1+` the `+` is completed.
+fn complete_name(line: &str, pos: usize, defined_names: &[String]) -> (usize, Vec<String>) {
+    let before = &line[..pos];
+    // Defined names can be longer than any primitive, so all the letters,
+    // digits, and underscores before the cursor are completed.
+    let name_start = before
+        .trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        .len();
+    let max_name_len = primitive_names().map(|name| name.len()).max().unwrap_or(0);
+    for (start, _) in before.char_indices() {
+        let partial = &before.as_bytes()[start..];
+        if partial[0].is_ascii_whitespace() {
+            continue;
+        }
+        let mut names: Vec<String> = if pos - start <= max_name_len {
+            primitive_names()
+                .filter(|name| name.starts_with(partial))
+                .map(|name| name.to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if start == name_start {
+            names.extend(
+                defined_names
+                    .iter()
+                    .filter(|name| name.as_bytes().starts_with(partial))
+                    .cloned(),
+            );
+        }
+        if !names.is_empty() {
+            names.sort();
+            names.dedup();
+            return (start, names);
+        }
+    }
+    (pos, Vec::new())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::word::Word;
+
+    #[test]
+    fn complete_primitives() {
+        assert_eq!(
+            complete_name("+", 1, &[]),
+            (0, vec!["+".to_owned(), "+.".to_owned(), "+:".to_owned()])
+        );
+        assert_eq!(
+            complete_name("1 + i", 5, &[]),
+            (4, vec!["i.".to_owned(), "i:".to_owned()])
+        );
+        assert_eq!(
+            complete_name("1+", 2, &[]),
+            (1, vec!["+".to_owned(), "+.".to_owned(), "+:".to_owned()])
+        );
+        assert_eq!(complete_name("@", 1, &[]), (0, vec!["@.".to_owned()]));
+        assert_eq!(complete_name("1 ", 2, &[]), (2, vec![]));
+        assert_eq!(complete_name("abc", 3, &[]), (3, vec![]));
+    }
+
+    #[test]
+    fn complete_adverbs() {
+        assert_eq!(complete_name("+/.", 3, &[]), (1, vec!["/.".to_owned()]));
+    }
+
+    #[test]
+    fn complete_defined_names() {
+        let mut session = Session::new();
+        for (name, sentence) in [("addten", "+"), ("item_count", "$")] {
+            match session.eval_line(sentence) {
+                Ok(Some(Word::Verb(verb))) => session.define_verb(name, verb),
+                other => panic!("{:?}", other),
+            }
+        }
+        let names = session.defined_names();
+        assert_eq!(
+            complete_name("addt", 4, &names),
+            (0, vec!["addten".to_owned()])
+        );
+        assert_eq!(
+            complete_name("1 + i", 5, &names),
+            (
+                4,
+                vec!["i.".to_owned(), "i:".to_owned(), "item_count".to_owned()]
+            )
+        );
+        assert_eq!(
+            complete_name("+/ item_c", 9, &names),
+            (3, vec!["item_count".to_owned()])
+        );
+        assert_eq!(complete_name("xaddt", 5, &names), (5, vec![]));
+    }
+
+    #[test]
+    fn truncate_tall_output() {
+        assert_eq!(truncate_rows("1\n2\n3", 3), "1\n2\n3");
+        assert_eq!(truncate_rows("1\n2\n3\n4\n5", 3), "1\n2\n... 3 more rows");
+    }
+
+    #[test]
+    fn profile_errors_are_reported_by_line() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"1 + 2\nNB. ok\n\n1 % 'a'\n").unwrap();
+        let path = file.path();
+        assert_eq!(
+            load_profile(&mut Session::new(), path),
+            [format!("{}:4: error: Domain", path.display())]
+        );
+    }
+
+    #[test]
+    fn missing_profile_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let messages = load_profile(&mut Session::new(), &dir.path().join("profile.ijs"));
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("profile.ijs"));
+    }
+}
@@ -9,19 +9,109 @@
 //! single "word": this accounts for how `* 1 2 3` "knows" to multiply all the numbers: they're
 //! effectively a single word which constitues the argument.
 
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::str::FromStr;
 
 use num_complex::Complex64;
 
+use crate::adverb::Adverb;
+use crate::array::Array;
 use crate::atom::Atom;
+use crate::conjunction::Conjunction;
 use crate::error::{Error, Result};
+use crate::explicit::{Execute, Explicit};
 use crate::lex::Lex;
 use crate::noun::Noun;
 use crate::primitive::Primitive;
 use crate::word::{Sentence, Word};
 
+/// Words defined by name, such as verbs defined by a program embedding rsj,
+/// or the arguments of an explicit definition.
+///
+/// These are looked up before the primitives, so they can either add new
+/// names, or replace primitives.
+pub type Names = HashMap<Vec<u8>, Word>;
+
 pub fn scan_sentence(s: &str) -> Result<Sentence> {
-    Sentence::scan(&mut Lex::new(s.as_bytes())).map(|os| os.unwrap_or_default())
+    scan_sentence_with_names(s, &Names::new())
+}
+
+/// Scan a sentence, looking up names first in `names` and then in the
+/// primitives.
+pub fn scan_sentence_with_names(s: &str, names: &Names) -> Result<Sentence> {
+    let mut lex = Lex::new(s.as_bytes());
+    let mut sentence: Sentence = Vec::new();
+    while let Some(word) = scan_word(&mut lex, names)? {
+        sentence.push(word);
+    }
+    Ok(sentence)
+}
+
+/// The characters that start primitive words, other than the primitives that
+/// are spelled with letters, such as `i.`.
+const SYMBOLS: &[u8] = b"!\"#$%&*+,-./;<=>?@[\\]^`{|}~";
+
+/// Split a sentence into the text of each of its words, as J's `;:` does.
+///
+/// Names are split out whether or not they're defined, and a comment is one
+/// word.
+pub(crate) fn word_texts(s: &str) -> Result<Vec<&str>> {
+    let mut lex = Lex::new(s.as_bytes());
+    let mut words = Vec::new();
+    loop {
+        lex.drop_whitespace();
+        if lex.is_end() {
+            break;
+        }
+        let start = lex.pos();
+        if lex.starts_with(b"NB.") {
+            lex.drop_line();
+        } else if lex.peek().is_ascii_alphabetic() {
+            lex.take_n(name_len(&lex).1);
+        } else if lex.starts_with(b"{{") || lex.starts_with(b"}}") {
+            // The words inside an explicit definition are shown separately.
+            lex.take_n(2);
+        } else if lex.take_any(SYMBOLS).is_some() {
+            // Primitives are split out whether or not they're implemented.
+            let _ = lex.take_any(b".:");
+        } else {
+            scan_word(&mut lex, &Names::new())?;
+        }
+        words.push(s[start..lex.pos()].trim_end());
+    }
+    Ok(words)
+}
+
+/// The length of the name at the cursor, which is a letter followed by
+/// letters, digits, and underscores, and then any number of inflections.
+///
+/// Returns the length of the name without its inflections, and the length
+/// including them.
+fn name_len(lex: &Lex) -> (usize, usize) {
+    let mut len = 0;
+    while lex
+        .lookahead(len)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_')
+    {
+        len += 1;
+    }
+    let letters = len;
+    while lex.lookahead(len).is_some_and(|c| c == b'.' || c == b':') {
+        len += 1;
+    }
+    (letters, len)
+}
+
+/// True if the name of length `len` at the start of `lex` is followed by a
+/// copula, `=:` or `=.`, so that it's about to be assigned rather than
+/// looked up.
+fn is_assigned(lex: &Lex, len: usize) -> bool {
+    let mut i = len;
+    while lex.lookahead(i).is_some_and(|c| c.is_ascii_whitespace()) {
+        i += 1;
+    }
+    lex.lookahead(i) == Some(b'=') && matches!(lex.lookahead(i + 1), Some(b':' | b'.'))
 }
 
 /// Scan from characters into objects.
@@ -37,63 +127,124 @@ trait Scan {
         Self: Sized;
 }
 
-impl Scan for Sentence {
-    fn scan(lex: &mut Lex) -> Result<Option<Sentence>> {
-        let mut sentence: Sentence = Vec::new();
-        while let Some(word) = Word::scan(lex)? {
-            sentence.push(word);
+/// Take one word, if there is one.
+fn scan_word(lex: &mut Lex, names: &Names) -> Result<Option<Word>> {
+    loop {
+        lex.drop_whitespace();
+        if lex.is_end() {
+            return Ok(None);
+        }
+        if lex.starts_with(b"NB.") {
+            lex.drop_line();
+        } else {
+            break;
         }
-        Ok(Some(sentence))
+    }
+    if lex.take_if(b'\'') {
+        return scan_string(lex).map(|n| Some(Word::Noun(n)));
+    } else if lex.starts_with(b"{{") {
+        lex.take_n(2);
+        return scan_definition(lex, names).map(Some);
+    } else if let Some(sym) = lex.take_any(SYMBOLS) {
+        let mut s = vec![sym];
+        if let Some(dots) = lex.take_any(b".:") {
+            s.push(dots);
+        }
+        if s == b"=:" || s == b"=." {
+            return Ok(Some(Word::Copula));
+        }
+        return primitive_word(&s, names).map(Some);
+    } else if lex.peek().is_ascii_alphabetic() {
+        let (letters, len) = name_len(lex);
+        if len > letters {
+            return primitive_word(lex.take_n(len), names).map(Some);
+        } else if is_assigned(lex, len) {
+            return Ok(Some(Word::Name(lex.take_n(len).to_vec())));
+        } else if let Some(word) = names.get(lex.peek_n(len)) {
+            lex.take_n(len);
+            return Ok(Some(word.clone()));
+        }
+    } else if lex.take_if(b'(') {
+        return Ok(Some(Word::OpenParen));
+    } else if lex.take_if(b')') {
+        return Ok(Some(Word::CloseParen));
+    }
+    // Take as many contiguous numbers as we can as one list-of-numbers "word".
+    let mut numbers: Vec<Atom> = Vec::new();
+    while let Some(number) = Complex64::scan(lex)? {
+        numbers.push(number.into());
+        lex.drop_whitespace();
+    }
+    if numbers.len() == 1 {
+        Ok(Some(Word::Noun(Noun::Atom(numbers.remove(0)))))
+    } else if !numbers.is_empty() {
+        Ok(Some(Word::Noun(Noun::from(numbers))))
+    } else if lex.is_end() {
+        Ok(None)
+    } else {
+        Err(Error::Unexpected(lex.peek() as char))
     }
 }
 
-impl Scan for Word {
-    fn scan(lex: &mut Lex) -> Result<Option<Word>> {
-        loop {
-            lex.drop_whitespace();
-            if lex.is_end() {
-                return Ok(None);
-            }
-            if lex.starts_with(b"NB.") {
-                lex.drop_line();
-            } else {
-                break;
-            }
-        }
-        if let Some(sym) = lex.take_any(b"#$%&*+-/<=>?@") {
-            let mut s = vec![sym];
-            if let Some(dots) = lex.take_any(b".:") {
-                s.push(dots);
-            }
-            return Ok(Some(Word::Verb(Primitive::by_name(&s)?)));
-        } else if lex.peek().is_ascii_alphabetic() {
-            if let Some(dots) = lex.lookahead(1) {
-                if dots == b'.' || dots == b':' {
-                    let s = vec![lex.take(), lex.take()];
-                    return Ok(Some(Word::Verb(Primitive::by_name(&s)?)));
+/// Look up the word for a defined name, or a primitive verb or modifier.
+fn primitive_word(s: &[u8], names: &Names) -> Result<Word> {
+    if let Some(word) = names.get(s) {
+        Ok(word.clone())
+    } else if s == b"\"." {
+        Ok(Word::Verb(Rc::new(Execute::new(names.clone()))))
+    } else if let Some(conj) = Conjunction::by_name(&s) {
+        Ok(Word::Conjunction(conj))
+    } else if let Some(adverb) = Adverb::by_name(&s) {
+        Ok(Word::Adverb(adverb))
+    } else {
+        Primitive::by_name(&s).map(Word::from)
+    }
+}
+
+/// Scan a quoted string, after the opening quote.
+///
+/// Quotes within the string are written as two quotes. A string of one character is an atom;
+/// any other string is a list.
+fn scan_string(lex: &mut Lex) -> Result<Noun> {
+    let mut s = Vec::new();
+    loop {
+        match lex.try_peek() {
+            None => return Err(Error::OpenQuote),
+            Some(b'\'') => {
+                lex.drop();
+                if lex.take_if(b'\'') {
+                    s.push(b'\'');
+                } else {
+                    break;
                 }
             }
-        } else if lex.take_if(b'(') {
-            return Ok(Some(Word::OpenParen));
-        } else if lex.take_if(b')') {
-            return Ok(Some(Word::CloseParen));
-        }
-        // Take as many contiguous numbers as we can as one list-of-numbers "word".
-        let mut numbers: Vec<Atom> = Vec::new();
-        while let Some(number) = Complex64::scan(lex)? {
-            numbers.push(number.into());
-            lex.drop_whitespace();
+            Some(_) => s.push(lex.take()),
         }
-        if numbers.len() == 1 {
-            Ok(Some(Word::Noun(Noun::Atom(numbers.remove(0)))))
-        } else if !numbers.is_empty() {
-            Ok(Some(Word::Noun(Noun::from(numbers))))
-        } else if lex.is_end() {
-            Ok(None)
-        } else {
-            Err(Error::Unexpected(lex.peek() as char))
+    }
+    if s.len() == 1 {
+        Ok(Noun::Atom(Atom::Char(s[0])))
+    } else {
+        let chars: Array = s.into_iter().map(Atom::Char).collect();
+        Ok(Noun::Array(chars.with_fill(Atom::Char(b' '))))
+    }
+}
+
+/// Scan an explicit definition, after the opening `{{`, up to the closing
+/// `}}`.
+///
+/// The body is kept as text, and evaluated each time the verb is applied, with
+/// the names currently defined.
+fn scan_definition(lex: &mut Lex, names: &Names) -> Result<Word> {
+    let mut body = Vec::new();
+    while !lex.starts_with(b"}}") {
+        if lex.is_end() {
+            return Err(Error::OpenDefinition);
         }
+        body.push(lex.take());
     }
+    lex.take_n(2);
+    let body = String::from_utf8_lossy(&body).trim().to_owned();
+    Ok(Word::Verb(Rc::new(Explicit::new(body, names.clone()))))
 }
 
 /// Take one number, if there is one.
@@ -103,13 +254,12 @@ impl Scan for Complex64 {
             return Ok(None);
         }
         if lex.peek().is_ascii_digit() || lex.peek() == b'_' {
-            // TODO: Parse complex numbers with j
             // TODO: `x` and `p` for polar coordinates?
             // TODO: More forms from https://www.jsoftware.com/help/dictionary/dcons.htm.
             let mut num_str = String::new();
             while let Some(c) = lex.try_peek() {
                 match c {
-                    b'.' | b'0'..=b'9' | b'e' => {
+                    b'.' | b'0'..=b'9' | b'e' | b'j' => {
                         // Note: This will accept '123.13.12313' but the later float parser will fail
                         // on it.
                         num_str.push(lex.take() as char);
@@ -122,12 +272,11 @@ impl Scan for Complex64 {
                     _ => break,
                 }
             }
-            let number = if num_str == "-" {
-                Complex64::new(f64::INFINITY, 0.0)
-            } else if num_str == "--" {
-                Complex64::new(f64::NEG_INFINITY, 0.0)
-            } else {
-                Complex64::from_str(&num_str).map_err(Error::ParseNumber)?
+            // A complex number is written as the real and imaginary parts
+            // separated by `j`, like `1j_2`.
+            let number = match num_str.split_once('j') {
+                Some((re, im)) => Complex64::new(parse_real(re)?, parse_real(im)?),
+                None => Complex64::new(parse_real(&num_str)?, 0.0),
             };
             Ok(Some(number))
         } else {
@@ -135,3 +284,18 @@ impl Scan for Complex64 {
         }
     }
 }
+
+/// Parse a real number, after `_` has been replaced by `-`.
+///
+/// `_` alone is infinity, and `__` is negative infinity.
+fn parse_real(s: &str) -> Result<f64> {
+    match s {
+        "-" => Ok(f64::INFINITY),
+        "--" => Ok(f64::NEG_INFINITY),
+        // The characters were already checked, so this will be a real number
+        // if it parses at all.
+        s => Complex64::from_str(s)
+            .map(|c| c.re)
+            .map_err(Error::ParseNumber),
+    }
+}
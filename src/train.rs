@@ -0,0 +1,79 @@
+// Copyright 2022 Martin Pool
+
+//! Trains: verbs formed from a sequence of verbs, such as forks and hooks.
+//!
+//! See <https://code.jsoftware.com/wiki/Vocabulary/fork> and
+//! <https://code.jsoftware.com/wiki/Vocabulary/hook>.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use crate::error::Result;
+use crate::noun::Noun;
+use crate::verb::Verb;
+
+/// The left tine of a fork, which may be either a verb or a constant noun.
+#[derive(Debug)]
+pub enum Tine {
+    Verb(Rc<dyn Verb>),
+    Noun(Noun),
+}
+
+/// A fork `(f g h)`: `(f y) g (h y)`, or `(x f y) g (x h y)`.
+///
+/// If `f` is a noun, it is used directly as the left argument of `g`.
+#[derive(Debug)]
+pub struct Fork {
+    pub f: Tine,
+    pub g: Rc<dyn Verb>,
+    pub h: Rc<dyn Verb>,
+}
+
+impl Verb for Fork {
+    fn display(&self) -> Cow<'_, str> {
+        let f = match &self.f {
+            Tine::Verb(f) => f.display().into_owned(),
+            Tine::Noun(n) => n.to_string(),
+        };
+        format!("{} {} {}", f, self.g.display(), self.h.display()).into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let right = self.h.monad(y)?;
+        let left = match &self.f {
+            Tine::Verb(f) => f.monad(y)?,
+            Tine::Noun(n) => n.clone(),
+        };
+        self.g.dyad(&left, &right)
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        let right = self.h.dyad(x, y)?;
+        let left = match &self.f {
+            Tine::Verb(f) => f.dyad(x, y)?,
+            Tine::Noun(n) => n.clone(),
+        };
+        self.g.dyad(&left, &right)
+    }
+}
+
+/// A hook `(f g)`: `y f (g y)`, or `x f (g y)`.
+#[derive(Debug)]
+pub struct Hook {
+    pub f: Rc<dyn Verb>,
+    pub g: Rc<dyn Verb>,
+}
+
+impl Verb for Hook {
+    fn display(&self) -> Cow<'_, str> {
+        format!("{} {}", self.f.display(), self.g.display()).into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        self.f.dyad(y, &self.g.monad(y)?)
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        self.f.dyad(x, &self.g.monad(y)?)
+    }
+}
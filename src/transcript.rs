@@ -2,23 +2,145 @@
 
 //! Handle J transcript files.
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::eval::Session;
 
+/// A sentence in a transcript whose output changed when it was rerun.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The input line, without its indent.
+    pub input: String,
+    /// The output previously in the transcript.
+    pub expected: String,
+    /// The new output, which may be an error.
+    pub output: String,
+    /// The verb applications in evaluating the sentence, if the session is
+    /// recording its trace.
+    pub trace: Vec<String>,
+}
+
+/// Rerun the input lines of a transcript, and return the transcript with their
+/// current output.
+///
+/// Input lines are indented by three spaces. The rest of the line is evaluated
+/// as is, so it may have more leading spaces, trailing whitespace, or a
+/// trailing comment.
+///
+/// If the transcript has CRLF line breaks, so does the result.
 pub fn rerun(session: &mut Session, ts: &str) -> Result<String> {
+    rerun_with_changes(session, ts).map(|(out, _changes)| out)
+}
+
+/// Rerun a transcript like [rerun], and also return a description of each
+/// sentence whose output changed.
+pub fn rerun_with_changes(session: &mut Session, ts: &str) -> Result<(String, Vec<Change>)> {
+    let newline = if ts.contains("\r\n") { "\r\n" } else { "\n" };
     let mut out = String::new();
-    for l in ts.lines() {
+    let mut changes = Vec::new();
+    let mut lines = ts.lines().peekable();
+    while let Some(l) = lines.next() {
         if let Some(s) = l.strip_prefix("   ") {
-            assert!(!s.starts_with(' ')); // no extra spaces: does not actually need to be true but might catch indentation bugs
             out.push_str(l);
-            out.push('\n');
-            let output = session.eval_text(s);
+            out.push_str(newline);
+            // The existing output is everything up to the next input line.
+            let mut expected = Vec::new();
+            while let Some(l) = lines.next_if(|l| !l.starts_with("   ")) {
+                expected.push(l);
+            }
+            while expected.last() == Some(&"") {
+                expected.pop();
+            }
+            let expected = expected.join("\n");
+            session.take_trace();
+            let result = session.eval_line(s);
+            let output =
+                if session.keep_unimplemented() && matches!(result, Err(Error::Unimplemented(_))) {
+                    expected.clone()
+                } else {
+                    session.format_result(result)
+                };
             if !output.is_empty() {
                 assert!(!output.ends_with('\n'));
-                out.push_str(&output);
-                out.push('\n');
+                out.push_str(&output.replace('\n', newline));
+                out.push_str(newline);
+            }
+            if output != expected {
+                changes.push(Change {
+                    input: s.to_owned(),
+                    expected,
+                    output,
+                    trace: session.take_trace(),
+                });
             }
         }
     }
-    Ok(out)
+    Ok((out, changes))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn trailing_comment_and_whitespace() -> Result<()> {
+        let mut session = Session::new();
+        assert_eq!(
+            rerun(&mut session, "   3 + 4   NB. add\n")?,
+            "   3 + 4   NB. add\n7\n"
+        );
+        assert_eq!(rerun(&mut session, "   3 + 4  \t\n")?, "   3 + 4  \t\n7\n");
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_line_breaks_are_kept() -> Result<()> {
+        let mut session = Session::new();
+        assert_eq!(
+            rerun(&mut session, "   1 + 1\r\n3\r\n   2 2 $ 1\r\n")?,
+            "   1 + 1\r\n2\r\n   2 2 $ 1\r\n1 1\r\n1 1\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn extra_spaces_after_prompt() -> Result<()> {
+        let mut session = Session::new();
+        assert_eq!(rerun(&mut session, "     3 + 4\n0\n")?, "     3 + 4\n7\n");
+        Ok(())
+    }
+
+    #[test]
+    fn report_changes_with_trace() -> Result<()> {
+        let mut session = Session::new();
+        session.set_record_trace(true);
+        let (out, changes) =
+            rerun_with_changes(&mut session, "   1 + 1\n2\n   - 2 + 3\n5\n   1 % 'a'\n")?;
+        assert_eq!(
+            out,
+            "   1 + 1\n2\n   - 2 + 3\n_5\n   1 % 'a'\nerror: Domain\n"
+        );
+        assert_eq!(
+            changes,
+            [
+                Change {
+                    input: "- 2 + 3".to_owned(),
+                    expected: "5".to_owned(),
+                    output: "_5".to_owned(),
+                    trace: vec![
+                        "dyad (2) + (3) => 5".to_owned(),
+                        "monad - (5) => _5".to_owned()
+                    ],
+                },
+                Change {
+                    input: "1 % 'a'".to_owned(),
+                    expected: String::new(),
+                    output: "error: Domain".to_owned(),
+                    trace: vec![],
+                },
+            ]
+        );
+        Ok(())
+    }
 }
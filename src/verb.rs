@@ -8,19 +8,175 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::rc::Rc;
 
-use crate::error::Result;
+use crate::atom::Atom;
+use crate::error::{Error, Result};
 use crate::noun::Noun;
+use crate::primitive::Primitive;
 
 /// A verb, whether primitive or derived.
 pub trait Verb: fmt::Debug {
     // TODO: ranks...
 
-    fn display(&self) -> Cow<str>;
+    fn display(&self) -> Cow<'_, str>;
 
     /// Evaluate this verb as a monad.
     fn monad(&self, y: &Noun) -> Result<Noun>;
 
     /// Evaluate this verb as a dyad.
     fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun>;
+
+    /// The rank of the verb's monad: the rank of the cells to which it applies.
+    fn monad_rank(&self) -> Rank {
+        Rank::Infinite
+    }
+
+    /// Return the inverse of this verb as a monad, which undoes its effect.
+    ///
+    /// Returns [Error::Domain] if the verb has no known inverse.
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        Err(Error::Domain)
+    }
+
+    /// The identity element of the verb as a dyad, if it has one: the value
+    /// `e` such that `e u y` is `y`.
+    ///
+    /// This is the result of inserting the verb between the items of an empty
+    /// list.
+    fn identity(&self) -> Option<Atom> {
+        None
+    }
+
+    /// The primitive that this verb is, if it's a primitive verb rather than
+    /// a derived or user-defined verb.
+    fn as_primitive(&self) -> Option<&Primitive> {
+        None
+    }
+}
+
+/// The rank of a verb: the rank of the cells of its argument to which it
+/// applies.
+///
+/// See <https://code.jsoftware.com/wiki/Vocabulary/Rank>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    Finite(usize),
+    /// The verb applies to cells this many axes smaller than the argument,
+    /// such as its items for `"_1`.
+    Negative(usize),
+    /// The verb applies to the whole argument, whatever its rank.
+    Infinite,
+}
+
+impl Rank {
+    /// Return the rank of the cells to which a verb of this rank applies, within an argument of
+    /// rank `arg_rank`.
+    pub fn cell_rank(self, arg_rank: usize) -> usize {
+        match self {
+            Rank::Finite(r) => r.min(arg_rank),
+            Rank::Negative(r) => arg_rank.saturating_sub(r),
+            Rank::Infinite => arg_rank,
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rank::Finite(r) => write!(f, "{}", r),
+            Rank::Negative(r) => write!(f, "_{}", r),
+            Rank::Infinite => f.write_str("_"),
+        }
+    }
+}
+
+/// Apply a function to each cell of y of the given rank, and assemble the
+/// results.
+pub fn monad_at_rank<F>(y: &Noun, rank: Rank, f: F) -> Result<Noun>
+where
+    F: Fn(&Noun) -> Result<Noun>,
+{
+    let cell_rank = rank.cell_rank(y.dims().len());
+    if cell_rank == y.dims().len() {
+        return f(y);
+    }
+    let (frame, cells) = y.cells(cell_rank);
+    let results = cells.iter().map(f).collect::<Result<Vec<Noun>>>()?;
+    Noun::assemble(frame, &results)
+}
+
+/// Apply a function to corresponding cells of x and y, of the given ranks, and
+/// assemble the results.
+///
+/// The frames of x and y must agree: one must be a prefix of the other. Each
+/// cell of the argument with the shorter frame is paired with every cell in the
+/// corresponding part of the longer frame.
+///
+/// Returns [Error::Length] if frames of the same rank have different lengths,
+/// or [Error::Rank] if frames of different ranks don't agree.
+pub fn dyad_at_rank<F>(x: &Noun, y: &Noun, ranks: (Rank, Rank), f: F) -> Result<Noun>
+where
+    F: Fn(&Noun, &Noun) -> Result<Noun>,
+{
+    let x_cell_rank = ranks.0.cell_rank(x.dims().len());
+    let y_cell_rank = ranks.1.cell_rank(y.dims().len());
+    if x_cell_rank == x.dims().len() && y_cell_rank == y.dims().len() {
+        return f(x, y);
+    }
+    let (x_frame, x_cells) = x.cells(x_cell_rank);
+    let (y_frame, y_cells) = y.cells(y_cell_rank);
+    let common = x_frame.len().min(y_frame.len());
+    if x_frame[..common] != y_frame[..common] {
+        if x_frame.len() == y_frame.len() {
+            return Err(Error::Length);
+        } else {
+            return Err(Error::Rank);
+        }
+    }
+    let frame = if x_frame.len() > y_frame.len() {
+        x_frame
+    } else {
+        y_frame
+    };
+    let n = frame.iter().product::<usize>();
+    // Each cell of the shorter frame is repeated for this many cells in the longer frame.
+    let x_repeat = n.checked_div(x_cells.len()).unwrap_or(1);
+    let y_repeat = n.checked_div(y_cells.len()).unwrap_or(1);
+    let results = (0..n)
+        .map(|i| f(&x_cells[i / x_repeat], &y_cells[i / y_repeat]))
+        .collect::<Result<Vec<Noun>>>()?;
+    Noun::assemble(frame, &results)
+}
+
+/// A reference to a verb is also a verb: this allows static primitives to be
+/// held alongside derived verbs.
+impl<V: Verb + ?Sized> Verb for &V {
+    fn display(&self) -> Cow<'_, str> {
+        (**self).display()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        (**self).monad(y)
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        (**self).dyad(x, y)
+    }
+
+    fn monad_rank(&self) -> Rank {
+        (**self).monad_rank()
+    }
+
+    fn inverse(&self) -> Result<Rc<dyn Verb>> {
+        (**self).inverse()
+    }
+
+    fn identity(&self) -> Option<Atom> {
+        (**self).identity()
+    }
+
+    fn as_primitive(&self) -> Option<&Primitive> {
+        (**self).as_primitive()
+    }
 }
@@ -7,9 +7,13 @@
 //! effectively a single word which constitues the argument.
 
 use std::fmt;
+use std::rc::Rc;
 
+use crate::adverb::Adverb;
+use crate::conjunction::Conjunction;
 use crate::noun::Noun;
 use crate::primitive::Primitive;
+use crate::verb::Verb;
 
 /// A sentence (like a statement) of J code, on a single line.
 pub type Sentence = Vec<Word>;
@@ -18,10 +22,26 @@ pub type Sentence = Vec<Word>;
 ///
 /// Note that a list of numbers counts as a single word, even though it contains spaces.
 /// So, in J, `1 2 3 + 4 5 6` is three words.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Words are also the values manipulated while evaluating a sentence, so a verb
+/// here may be either a primitive or a verb derived during evaluation.
+#[derive(Debug, Clone)]
 pub enum Word {
     Noun(Noun),
-    Verb(&'static Primitive),
+    Verb(Rc<dyn Verb>),
+    /// A list of verbs, formed by the tie conjunction `` ` ``.
+    ///
+    /// In J, a gerund is a noun containing a representation of each verb;
+    /// here it's held directly as a separate kind of word, which can be used
+    /// as the operand of a conjunction.
+    Gerund(Vec<Rc<dyn Verb>>),
+    Adverb(&'static Adverb),
+    Conjunction(&'static Conjunction),
+    /// A name that's about to be assigned a value, by a following copula.
+    Name(Vec<u8>),
+    /// `=:` or `=.`, which assigns the value on its right to the name on its
+    /// left.
+    Copula,
     OpenParen,
     CloseParen,
 }
@@ -32,11 +52,51 @@ impl From<Noun> for Word {
     }
 }
 
+impl From<&'static Primitive> for Word {
+    fn from(p: &'static Primitive) -> Word {
+        Word::Verb(Rc::new(p))
+    }
+}
+
+/// Words are equal if they're the same noun or syntax, or if they're verbs
+/// with the same representation.
+impl PartialEq for Word {
+    fn eq(&self, other: &Word) -> bool {
+        match (self, other) {
+            (Word::Noun(a), Word::Noun(b)) => a == b,
+            (Word::Verb(a), Word::Verb(b)) => a.display() == b.display(),
+            (Word::Gerund(a), Word::Gerund(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.display() == b.display())
+            }
+            (Word::Adverb(a), Word::Adverb(b)) => a.name() == b.name(),
+            (Word::Conjunction(a), Word::Conjunction(b)) => a.name() == b.name(),
+            (Word::Name(a), Word::Name(b)) => a == b,
+            (Word::Copula, Word::Copula)
+            | (Word::OpenParen, Word::OpenParen)
+            | (Word::CloseParen, Word::CloseParen) => true,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Display for Word {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Word::Noun(noun) => noun.fmt(f),
-            Word::Verb(verb) => verb.fmt(f),
+            Word::Verb(verb) => f.write_str(&verb.display()),
+            Word::Gerund(verbs) => {
+                for (i, v) in verbs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("`")?;
+                    }
+                    f.write_str(&v.display())?;
+                }
+                Ok(())
+            }
+            Word::Adverb(adverb) => adverb.fmt(f),
+            Word::Conjunction(conj) => conj.fmt(f),
+            Word::Name(name) => f.write_str(&String::from_utf8_lossy(name)),
+            Word::Copula => f.write_str("=:"),
             Word::OpenParen => f.write_str("("),
             Word::CloseParen => f.write_str(")"),
         }
@@ -0,0 +1,86 @@
+// Copyright 2022 Martin Pool
+
+//! Tests for constructing arrays.
+
+use rsj::array::Array;
+use rsj::atom::Atom;
+use rsj::error::Error;
+use rsj::noun::Noun;
+
+fn atoms(n: usize) -> Vec<Atom> {
+    (0..n).map(Atom::from).collect()
+}
+
+#[test]
+fn from_shape_vec_builds_table() {
+    let a = Array::from_shape_vec(&[2, 3], atoms(6)).unwrap();
+    assert_eq!(a.dims(), &[2, 3]);
+    assert_eq!(a.number_items(), 2);
+    assert_eq!(a.to_string(), "0 1 2\n3 4 5");
+}
+
+#[test]
+fn from_shape_vec_with_empty_axis() {
+    let a = Array::from_shape_vec(&[3, 0], vec![]).unwrap();
+    assert_eq!(a.dims(), &[3, 0]);
+}
+
+#[test]
+fn from_shape_vec_rejects_wrong_length() {
+    assert!(matches!(
+        Array::from_shape_vec(&[2, 2], atoms(3)),
+        Err(Error::Length)
+    ));
+    assert!(matches!(
+        Array::from_shape_vec(&[2], atoms(3)),
+        Err(Error::Length)
+    ));
+}
+
+#[test]
+fn singleton_list_keeps_its_rank() {
+    let a = Array::from_shape_vec(&[1], atoms(1)).unwrap();
+    assert_eq!(a.dims(), &[1]);
+    assert_eq!(a.number_items(), 1);
+    assert_eq!(a.to_string(), "0");
+    let noun = Noun::from(a);
+    assert_eq!(noun.dims(), &[1]);
+    assert!(matches!(noun, Noun::Array(_)));
+    assert_eq!(Noun::from(atoms(1)).dims(), &[1]);
+}
+
+#[test]
+fn rank_zero_array_becomes_atom() {
+    let a = Array::from_shape_vec(&[], atoms(1)).unwrap();
+    assert_eq!(a.dims(), &[] as &[usize]);
+    assert!(matches!(Noun::from(a), Noun::Atom(_)));
+}
+
+#[test]
+fn reshape_repeats_atoms_cyclically() {
+    let a = Array::from_vec(atoms(3)).reshape(&[2, 4]).unwrap();
+    assert_eq!(a.dims(), &[2, 4]);
+    assert_eq!(a.to_string(), "0 1 2 0\n1 2 0 1");
+}
+
+#[test]
+fn reshape_truncates_atoms() {
+    let a = Array::from_shape_vec(&[2, 3], atoms(6)).unwrap();
+    let b = a.reshape(&[4]).unwrap();
+    assert_eq!(b, Array::from_vec(atoms(4)));
+    assert_eq!(a.reshape(&[0, 5]).unwrap().dims(), &[0, 5]);
+}
+
+#[test]
+fn reshape_empty_array_fills_with_zeros() {
+    let a = Array::empty().reshape(&[3]).unwrap();
+    assert_eq!(a, Array::from_vec(vec![Atom::zero(); 3]));
+}
+
+#[test]
+fn reshape_too_large() {
+    assert!(matches!(
+        Array::from_vec(atoms(1)).reshape(&[1 << 20, 0, 1 << 20]),
+        Err(Error::OutOfMemory)
+    ));
+}
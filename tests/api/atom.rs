@@ -0,0 +1,40 @@
+// Copyright 2022 Martin Pool
+
+//! Tests for atoms.
+
+use rsj::atom::{Atom, EXACT_INTEGER_LIMIT};
+
+#[test]
+fn atom_from_small_usize() {
+    assert_eq!(Atom::from(42usize).try_to_f64(), Some(42.0));
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn atom_from_usize_larger_than_u32() {
+    let v = u32::MAX as usize + 1;
+    assert_eq!(Atom::from(v).try_to_f64(), Some(4294967296.0));
+    assert_eq!(Atom::from(usize::MAX).try_to_f64(), Some(usize::MAX as f64));
+}
+
+#[test]
+fn exact_integers() {
+    assert!(Atom::from(0.0).is_exact_integer());
+    assert!(Atom::from(-42.0).is_exact_integer());
+    assert!(Atom::from(EXACT_INTEGER_LIMIT).is_exact_integer());
+    assert!(Atom::from(-EXACT_INTEGER_LIMIT).is_exact_integer());
+    assert!(!Atom::from(EXACT_INTEGER_LIMIT * 2.0).is_exact_integer());
+    assert!(!Atom::from(0.5).is_exact_integer());
+    assert!(!Atom::from(f64::INFINITY).is_exact_integer());
+    assert!(!Atom::Char(b'a').is_exact_integer());
+}
+
+#[test]
+fn inexact_integers() {
+    assert!(!Atom::from(EXACT_INTEGER_LIMIT).is_inexact_integer());
+    assert!(!Atom::from(EXACT_INTEGER_LIMIT - 1.0).is_inexact_integer());
+    assert!(Atom::from(EXACT_INTEGER_LIMIT + 2.0).is_inexact_integer());
+    assert!(Atom::from(-1e300).is_inexact_integer());
+    assert!(!Atom::from(f64::NEG_INFINITY).is_inexact_integer());
+    assert!(!Atom::from(1.5).is_inexact_integer());
+}
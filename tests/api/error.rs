@@ -0,0 +1,34 @@
+// Copyright 2022 Martin Pool
+
+//! Tests for the kinds of errors.
+
+use rsj::error::ErrorKind;
+use rsj::eval::Session;
+
+fn error_kind(sentence: &str) -> ErrorKind {
+    Session::new().eval_line(sentence).unwrap_err().kind()
+}
+
+#[test]
+fn runtime_error_kinds() {
+    assert_eq!(error_kind("1 + 'a'"), ErrorKind::Domain);
+    assert_eq!(error_kind("1 2 + 3 4 5"), ErrorKind::Length);
+    assert_eq!(error_kind("(2 3 $ 1) + 1 2 3"), ErrorKind::Rank);
+    assert_eq!(error_kind("1e9 1e9 $ 0"), ErrorKind::OutOfMemory);
+    assert_eq!(error_kind("1 + /"), ErrorKind::Syntax);
+    assert_eq!(error_kind(">:^:_ (0)"), ErrorKind::Limit);
+    assert_eq!(error_kind("{{ x + y }} 1"), ErrorKind::Valence);
+}
+
+#[test]
+fn scanner_errors_are_parse_errors() {
+    assert_eq!(error_kind("'abc"), ErrorKind::Parse);
+    assert_eq!(error_kind("(1 + 2"), ErrorKind::Parse);
+    assert_eq!(error_kind("1 $ {{ y"), ErrorKind::Parse);
+}
+
+#[test]
+fn io_errors() {
+    let err = rsj::error::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+    assert_eq!(err.kind(), ErrorKind::Io);
+}
@@ -32,6 +32,7 @@ fn examples_j() {
 
 fn run_j_example(path: &Path) {
     let mut session = Session::new();
+    session.set_ascii_boxes(true);
     let body = fs::read_to_string(path).unwrap();
     let mut lines = body.lines();
     while let Some(input) = lines.next() {
@@ -42,10 +43,11 @@ fn run_j_example(path: &Path) {
         let input = input.strip_prefix(PROMPT).expect("prompt on input line");
         let output = session.eval_text(input);
 
-        if !output.is_empty() {
+        // Multi-dimensional results span several lines.
+        for output_line in output.lines() {
             let expected = lines.next().unwrap();
             assert!(!expected.starts_with(PROMPT));
-            assert_eq!(output, expected);
+            assert_eq!(output_line, expected);
         }
     }
 }
@@ -0,0 +1,62 @@
+// Copyright 2022 Martin Pool
+
+//! Tests for flat results.
+
+use pretty_assertions::assert_eq;
+
+use rsj::error::Error;
+use rsj::eval::Session;
+use rsj::flat::{FlatResult, FlatType};
+
+#[test]
+fn flat_table() {
+    let flat = Session::new().eval_to_flat("2 3 $ i. 6").unwrap();
+    assert_eq!(
+        flat,
+        FlatResult {
+            shape: vec![2, 3],
+            reals: vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            imags: vec![0.0; 6],
+            atom_type: FlatType::Number,
+        }
+    );
+}
+
+#[test]
+fn flat_atom_and_complex() {
+    let flat = Session::new().eval_to_flat("1j_2").unwrap();
+    assert_eq!(flat.shape, Vec::<usize>::new());
+    assert_eq!(flat.reals, [1.0]);
+    assert_eq!(flat.imags, [-2.0]);
+}
+
+#[test]
+fn flat_chars() {
+    let flat = Session::new().eval_to_flat("'ab'").unwrap();
+    assert_eq!(flat.atom_type, FlatType::Char);
+    assert_eq!(flat.shape, [2]);
+    assert_eq!(flat.reals, [97.0, 98.0]);
+}
+
+#[test]
+fn flat_empty() {
+    let flat = Session::new().eval_to_flat("0 3 $ 0").unwrap();
+    assert_eq!(flat.shape, [0, 3]);
+    assert!(flat.reals.is_empty());
+    assert_eq!(flat.atom_type, FlatType::Number);
+}
+
+#[test]
+fn flat_rejects_boxes_and_verbs() {
+    let mut session = Session::new();
+    assert!(matches!(
+        session.eval_to_flat("< 1 2"),
+        Err(Error::Unimplemented(_))
+    ));
+    assert!(matches!(session.eval_to_flat("+/"), Err(Error::Domain)));
+    assert!(matches!(session.eval_to_flat(""), Err(Error::Domain)));
+    assert!(matches!(
+        session.eval_to_flat("1 2 + 3 4 5"),
+        Err(Error::Length)
+    ));
+}
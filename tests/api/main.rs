@@ -1,6 +1,12 @@
 // Copyright 2021 Martin Pool
 
+mod array;
+mod atom;
+mod error;
 mod examples;
+mod flat;
+mod noun;
 mod primitive;
 mod print;
 mod scan;
+mod session;
@@ -0,0 +1,133 @@
+// Copyright 2022 Martin Pool
+
+//! Tests for nouns.
+
+use pretty_assertions::assert_eq;
+
+use rsj::array::Array;
+use rsj::atom::Atom;
+use rsj::error::Error;
+use rsj::eval::Session;
+use rsj::noun::Noun;
+use rsj::word::Word;
+
+#[test]
+fn parse_number_list() {
+    let noun: Noun = "1 2 3".parse().unwrap();
+    assert_eq!(noun, Noun::Array(Array::from([1.0, 2.0, 3.0])));
+}
+
+#[test]
+fn parse_atoms() {
+    assert_eq!("42".parse::<Noun>().unwrap(), Noun::from(42.0));
+    assert_eq!("_".parse::<Noun>().unwrap(), Noun::from(f64::INFINITY));
+    assert_eq!("'a'".parse::<Noun>().unwrap(), Noun::Atom(Atom::Char(b'a')));
+}
+
+#[test]
+fn parse_string() {
+    assert_eq!("'abc'".parse::<Noun>().unwrap(), Noun::char_list("abc"));
+}
+
+#[test]
+fn parse_noun_in_parens() {
+    assert_eq!(
+        "((1 2))".parse::<Noun>().unwrap(),
+        "1 2".parse::<Noun>().unwrap()
+    );
+}
+
+#[test]
+fn parse_rejects_verbs() {
+    assert!(matches!("1 + 2".parse::<Noun>(), Err(Error::SyntaxError)));
+    assert!(matches!("i. 3".parse::<Noun>(), Err(Error::SyntaxError)));
+    assert!(matches!("+".parse::<Noun>(), Err(Error::SyntaxError)));
+}
+
+#[test]
+fn parse_rejects_anything_but_one_noun() {
+    assert!(matches!("".parse::<Noun>(), Err(Error::SyntaxError)));
+    assert!(matches!(
+        "(1 2) (3 4)".parse::<Noun>(),
+        Err(Error::SyntaxError)
+    ));
+    assert!(matches!(
+        "(1 2".parse::<Noun>(),
+        Err(Error::UnbalancedParens)
+    ));
+    assert!(matches!("'abc".parse::<Noun>(), Err(Error::OpenQuote)));
+}
+
+#[test]
+fn type_names() {
+    let type_name = |s: &str| s.parse::<Noun>().unwrap().type_name();
+    assert_eq!(type_name("1"), "numeric");
+    assert_eq!(type_name("1 2.5 _"), "numeric");
+    assert_eq!(type_name("1j1"), "complex");
+    assert_eq!(type_name("1 2 3j4"), "complex");
+    assert_eq!(type_name("'a'"), "character");
+    assert_eq!(type_name("'abc'"), "character");
+    assert_eq!(type_name("''"), "empty");
+    assert_eq!(Noun::from(Array::empty()).type_name(), "empty");
+    assert_eq!(
+        Noun::from(Atom::Boxed(Box::new(Noun::from(1.0)))).type_name(),
+        "boxed"
+    );
+}
+
+#[test]
+fn fill_follows_type_of_empty_arrays() {
+    let fill = |s: &str| s.parse::<Noun>().unwrap().fill();
+    assert_eq!(fill("1 2"), Atom::zero());
+    assert_eq!(fill("'ab'"), Atom::Char(b' '));
+    assert_eq!(fill("''"), Atom::Char(b' '));
+    assert_eq!(Noun::from(Array::empty()).fill(), Atom::zero());
+    // Empty arrays are equal whatever their type.
+    assert_eq!("''".parse::<Noun>().unwrap(), Noun::from(Array::empty()));
+}
+
+#[test]
+fn to_vec_f64() {
+    let to_vec = |s: &str| s.parse::<Noun>().unwrap().to_vec_f64();
+    assert_eq!(to_vec("42").unwrap(), [42.0]);
+    assert_eq!(to_vec("1 2.5 _").unwrap(), [1.0, 2.5, f64::INFINITY]);
+    let table = Array::from_shape_vec(&[2, 3], (0..6).map(|i| Atom::from(i as f64)).collect());
+    assert_eq!(
+        Noun::from(table.unwrap()).to_vec_f64().unwrap(),
+        [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+    );
+    assert_eq!(to_vec("1j0 2").unwrap(), [1.0, 2.0]);
+    assert_eq!(to_vec("''").unwrap(), [] as [f64; 0]);
+    assert!(matches!(to_vec("1 2j3"), Err(Error::Domain)));
+    assert!(matches!(to_vec("'abc'"), Err(Error::Domain)));
+    let boxed = Noun::from(Atom::Boxed(Box::new(Noun::from(1.0))));
+    assert!(matches!(boxed.to_vec_f64(), Err(Error::Domain)));
+}
+
+/// There's no separate integer type: integer literals, and integers computed
+/// by verbs such as `$` and `i.`, are all stored as real numbers, and so
+/// compare equal and have the same type.
+#[test]
+fn integer_literals_and_computed_integers_have_one_representation() {
+    let eval = |s: &str| match Session::new().eval_line(s).unwrap() {
+        Some(Word::Noun(noun)) => noun,
+        other => panic!("{:?}", other),
+    };
+    for (literal, computed) in [
+        ("2 3", "$ 2 3 $ 0"),
+        ("0 1 2", "i. 3"),
+        ("3", "# 'abc'"),
+        ("123", "123.0"),
+        ("3", "3 % 1"),
+        ("_1", "- 1"),
+    ] {
+        let literal = eval(literal);
+        let computed = eval(computed);
+        assert_eq!(literal, computed);
+        assert!(literal.iter_atoms().all(Atom::is_exact_integer));
+        assert!(literal
+            .iter_atoms()
+            .zip(computed.iter_atoms())
+            .all(|(a, b)| a.same_type(b)));
+    }
+}
@@ -1,9 +1,87 @@
 // Copyright 2022 Martin Pool
 
-use rsj::primitive::Primitive;
+use proptest::prelude::*;
+
+use rsj::eval::Session;
+use rsj::noun::Noun;
+use rsj::primitive::{Primitive, PRIMITIVES};
+use rsj::word::Word;
 
 #[test]
 fn primitive_debug_repr() {
     let add = Primitive::by_name(&"+").unwrap();
     assert_eq!(format!("{:?}", add), r#"Primitive { name: "+" }"#);
 }
+
+/// Numbers that are likely to cause trouble, such as infinities, that would
+/// give NaN if not handled carefully.
+const SPECIAL_VALUES: &[&str] = &[
+    "0", "_0", "1", "_1", "2", "0.5", "_0.5", "_", "__", "1e300", "_1e300", "1j1", "0j_", "_j1",
+    "0 1 _ __",
+];
+
+/// J has no NaN, so no primitive should ever produce one, in whatever
+/// combination of special values.
+#[test]
+fn no_nan_results() {
+    let mut session = Session::new();
+    for prim in PRIMITIVES {
+        for y in SPECIAL_VALUES {
+            let sentence = format!("{} {}", prim.name(), y);
+            let result = session.eval_text(&sentence);
+            assert!(!result.contains("NaN"), "{} => {}", sentence, result);
+            for x in SPECIAL_VALUES {
+                let sentence = format!("({}) {} {}", x, prim.name(), y);
+                let result = session.eval_text(&sentence);
+                assert!(!result.contains("NaN"), "{} => {}", sentence, result);
+            }
+        }
+    }
+}
+
+proptest! {
+    /// `#. y` on bits, which uses bit operations, agrees with the general
+    /// `2 #. y`.
+    #[test]
+    fn base_2_matches_general_base(bits in prop::collection::vec(any::<bool>(), 1..60)) {
+        let bits = bits.iter().map(|&b| (b as u8).to_string()).collect::<Vec<_>>().join(" ");
+        let mut session = Session::new();
+        prop_assert_eq!(
+            session.eval_text(&format!("#. {}", bits)),
+            session.eval_text(&format!("2 #. {}", bits))
+        );
+    }
+
+    /// `#: y`, which uses bit operations, agrees with the general `x #: y`
+    /// with enough 2s in x for y.
+    #[test]
+    fn antibase_2_matches_general_antibase(y in 0u64..(1 << 53)) {
+        let len = u64::BITS - y.max(1).leading_zeros();
+        let mut session = Session::new();
+        prop_assert_eq!(
+            session.eval_line(&format!("#: {}", y)).unwrap(),
+            session.eval_line(&format!("({} $ 2) #: {}", len, y)).unwrap()
+        );
+        prop_assert_eq!(
+            session.eval_line(&format!("#. #: {}", y)).unwrap(),
+            Some(Word::Noun(Noun::from(y as f64)))
+        );
+    }
+}
+
+proptest! {
+    /// Verbs that count and rearrange items treat characters just as they
+    /// would numbers.
+    #[test]
+    fn structural_verbs_on_strings(a in "[a-z]{2,10}", b in "[a-z]{2,10}", n in 0usize..20) {
+        let mut session = Session::new();
+        let mut eval = |sentence: String| session.eval_text(&sentence);
+        let k = n % a.len();
+        assert_eq!(eval(format!("# '{a}'")), a.len().to_string());
+        assert_eq!(eval(format!("'{a}' , '{b}'")), format!("{a}{b}"));
+        assert_eq!(eval(format!("|. '{a}'")), a.chars().rev().collect::<String>());
+        assert_eq!(eval(format!("{n} |. '{a}'")), format!("{}{}", &a[k..], &a[..k]));
+        assert_eq!(eval(format!("{k} {{ '{a}'")), &a[k..=k]);
+        assert_eq!(eval(format!("{n} $ '{a}'")), a.chars().cycle().take(n).collect::<String>());
+    }
+}
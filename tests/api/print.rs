@@ -2,10 +2,14 @@
 
 //! Test printing values
 
+use num_complex::Complex64;
 use proptest::prelude::*;
 
 use rsj::array::Array;
 use rsj::atom::Atom;
+use rsj::noun::Noun;
+use rsj::scan::scan_sentence;
+use rsj::word::Word;
 
 proptest! {
     #[test]
@@ -32,3 +36,99 @@ proptest! {
         }
     }
 }
+
+proptest! {
+    #[test]
+    fn table_print_length_limited(a: Vec<f64>, ncols in 1usize..20) {
+        let lim = 80;
+        let nrows = a.len() / ncols;
+        let atoms = a[..nrows * ncols].iter().cloned().map(Atom::from).collect();
+        let arr = Array::from_shape_vec(&[nrows, ncols], atoms).unwrap();
+        let p = format!("{:.*}", lim, arr);
+        dbg!(&p);
+        for line in p.lines() {
+            if let Some(b) = line.strip_suffix(" ...") {
+                // If only one column fits, it's shown even if it's wider than
+                // the limit.
+                let b = b.trim_start();
+                if b.contains(' ') {
+                    assert!(line.len() <= lim);
+                }
+            } else {
+                assert!(line.len() <= lim, "with no elipsis the line should be shorter than the lim");
+            }
+        }
+    }
+
+    #[test]
+    fn tall_table_print_limited(nrows in 0usize..300) {
+        let atoms = (0..nrows).map(Atom::from).collect();
+        let arr = Array::from_shape_vec(&[nrows, 1], atoms).unwrap();
+        let p = format!("{:.*}", 80, arr);
+        assert!(p.lines().count() <= 101);
+        if nrows > 100 {
+            assert!(p.ends_with("\n..."));
+        }
+    }
+}
+
+#[test]
+fn negative_numbers_align_in_columns() {
+    let atoms = [1.0, -22.0, 3.0, -4.0, 5.0, -666.0]
+        .into_iter()
+        .map(Atom::from)
+        .collect();
+    let arr = Array::from_shape_vec(&[2, 3], atoms).unwrap();
+    assert_eq!(arr.to_string(), " 1 _22    3\n_4   5 _666");
+}
+
+proptest! {
+    #[test]
+    fn integer_columns_right_aligned(a: Vec<i16>, ncols in 1usize..8) {
+        let nrows = a.len() / ncols;
+        prop_assume!(nrows > 0);
+        let atoms = a[..nrows * ncols].iter().map(|&i| Atom::from(i as f64)).collect();
+        let arr = Array::from_shape_vec(&[nrows, ncols], atoms).unwrap();
+        // Every number in a column ends at the same place, counting its `_` if
+        // it's negative.
+        let ends = |line: &str| -> Vec<usize> {
+            let b = line.as_bytes();
+            (0..b.len())
+                .filter(|&i| b[i] != b' ' && b.get(i + 1).is_none_or(|&c| c == b' '))
+                .collect()
+        };
+        let p = arr.to_string();
+        let first = ends(p.lines().next().unwrap());
+        assert_eq!(first.len(), ncols);
+        for line in p.lines() {
+            assert_eq!(ends(line), first, "in {}", p);
+        }
+    }
+}
+
+#[test]
+fn complex_signs() {
+    let show = |re, im| Atom::from(Complex64::new(re, im)).to_string();
+    assert_eq!(show(0.0, 1.0), "0j1");
+    assert_eq!(show(3.0, -4.0), "3j_4");
+    assert_eq!(show(-3.0, -4.5), "_3j_4.5");
+    assert_eq!(show(0.0, -1.0), "0j_1");
+    // Negative zeros, and a zero imaginary part, show as plain 0.
+    assert_eq!(show(-0.0, 0.0), "0");
+    assert_eq!(show(-0.0, -0.0), "0");
+    assert_eq!(show(2.0, -0.0), "2");
+    assert_eq!(show(f64::INFINITY, f64::NEG_INFINITY), "_j__");
+}
+
+proptest! {
+    #[test]
+    fn complex_display_scans_to_same_number(re in -1e300..1e300f64, im in -1e300..1e300f64) {
+        let atom = Atom::from(Complex64::new(re, im));
+        let text = atom.to_string();
+        assert_eq!(
+            scan_sentence(&text).unwrap(),
+            &[Word::Noun(Noun::Atom(atom))],
+            "{text} should scan to the same number"
+        );
+    }
+}
@@ -8,12 +8,16 @@
 
 use num_complex::Complex64;
 use pretty_assertions::assert_eq;
+use proptest::prelude::*;
 
+use rsj::adverb::Adverb;
 use rsj::array::Array;
+use rsj::atom::Atom;
+use rsj::conjunction::Conjunction;
 use rsj::error::Error;
 use rsj::noun::Noun;
 use rsj::primitive;
-use rsj::scan::scan_sentence;
+use rsj::scan::{scan_sentence, scan_sentence_with_names, Names};
 use rsj::word::Word;
 
 #[test]
@@ -48,6 +52,14 @@ fn negative() {
     assert_eq!(s, &[Word::Noun(Noun::from(-1.0))]);
 }
 
+#[test]
+fn complex() {
+    assert_eq!(
+        scan_sentence("1j_2").unwrap(),
+        &[Word::Noun(Noun::Atom(Complex64::new(1.0, -2.0).into()))]
+    );
+}
+
 #[test]
 fn infinities() {
     assert_eq!(
@@ -66,7 +78,7 @@ fn primitive() {
     let minus = &primitive::MINUS;
     assert_eq!(
         scan_sentence(" - -").unwrap(),
-        &[Word::Verb(minus), Word::Verb(minus),]
+        &[Word::from(minus), Word::from(minus),]
     );
 }
 
@@ -89,3 +101,136 @@ fn several_numbers_in_one_word() {
         ])))]
     );
 }
+
+#[test]
+fn quoted_strings() {
+    assert_eq!(
+        scan_sentence("'it''s'").unwrap(),
+        &[Word::Noun(Noun::char_list("it's"))]
+    );
+    assert_eq!(
+        scan_sentence("'a'").unwrap(),
+        &[Word::Noun(Noun::Atom(Atom::Char(b'a')))]
+    );
+    assert!(matches!(scan_sentence("'abc"), Err(Error::OpenQuote)));
+}
+
+#[test]
+fn comments_start_with_nb_dot_at_a_word_boundary() {
+    assert_eq!(
+        scan_sentence("3 NB. comment").unwrap(),
+        &[Word::Noun(Noun::from(3.0))]
+    );
+    assert_eq!(
+        scan_sentence("3 NB.").unwrap(),
+        &[Word::Noun(Noun::from(3.0))]
+    );
+    assert_eq!(scan_sentence("  NB. only a comment").unwrap(), &[]);
+    assert_eq!(
+        scan_sentence("'NB. in a string'").unwrap(),
+        &[Word::Noun(Noun::char_list("NB. in a string"))]
+    );
+    // Without the dot, or followed by more letters, it's a name.
+    assert!(matches!(scan_sentence("NB"), Err(Error::Unexpected('N'))));
+    let mut names = Names::new();
+    names.insert(b"NBx".to_vec(), Word::Noun(Noun::from(4.0)));
+    assert_eq!(
+        scan_sentence_with_names("NBx NB. four", &names).unwrap(),
+        &[Word::Noun(Noun::from(4.0))]
+    );
+    // Within a name, it's part of the name.
+    assert!(matches!(
+        scan_sentence("aNB.b"),
+        Err(Error::Unimplemented(_))
+    ));
+}
+
+#[test]
+fn number_list_in_parens() {
+    assert_eq!(
+        scan_sentence("(1 2 3)+10").unwrap(),
+        &[
+            Word::OpenParen,
+            Word::Noun(Noun::Array(Array::from([1.0, 2.0, 3.0]))),
+            Word::CloseParen,
+            Word::from(&primitive::PLUS),
+            Word::Noun(Noun::from(10.0)),
+        ]
+    );
+}
+
+#[test]
+fn adverb() {
+    let words = scan_sentence("</.").unwrap();
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[1], Word::Adverb(Adverb::by_name(&"/.").unwrap()));
+}
+
+#[test]
+fn dot_separated_from_adverb() {
+    let words = scan_sentence("+/ . *").unwrap();
+    assert_eq!(words.len(), 4);
+    assert_eq!(words[1], Word::Adverb(Adverb::by_name(&"/").unwrap()));
+    assert_eq!(
+        words[2],
+        Word::Conjunction(Conjunction::by_name(&".").unwrap())
+    );
+}
+
+#[test]
+fn infinities_in_number_list() {
+    assert_eq!(
+        scan_sentence("1 _ 2").unwrap(),
+        &[Word::Noun(Noun::Array(Array::from([
+            1.0,
+            f64::INFINITY,
+            2.0
+        ])))]
+    );
+    assert_eq!(
+        scan_sentence("_1 _ _2").unwrap(),
+        &[Word::Noun(Noun::Array(Array::from([
+            -1.0,
+            f64::INFINITY,
+            -2.0
+        ])))]
+    );
+    assert_eq!(
+        scan_sentence("__ _ 1").unwrap(),
+        &[Word::Noun(Noun::Array(Array::from([
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            1.0
+        ])))]
+    );
+}
+
+/// Any real number, including infinities, but not NaN, which J doesn't have.
+fn real() -> impl Strategy<Value = f64> {
+    any::<f64>().prop_filter("not NaN", |x| !x.is_nan())
+}
+
+proptest! {
+    /// Displaying a number and then scanning it gives back exactly the same
+    /// number.
+    #[test]
+    fn display_then_scan_real(x in real()) {
+        let atom = Atom::from(x);
+        let words = scan_sentence(&atom.to_string()).unwrap();
+        prop_assert_eq!(words, vec![Word::Noun(Noun::Atom(atom))]);
+    }
+
+    #[test]
+    fn display_then_scan_complex(re in real(), im in real()) {
+        let atom = Atom::from(Complex64::new(re, im));
+        let words = scan_sentence(&atom.to_string()).unwrap();
+        prop_assert_eq!(words, vec![Word::Noun(Noun::Atom(atom))]);
+    }
+
+    #[test]
+    fn display_then_scan_list(xs in prop::collection::vec(real(), 2..20)) {
+        let array = Array::from(xs);
+        let words = scan_sentence(&array.to_string()).unwrap();
+        prop_assert_eq!(words, vec![Word::Noun(Noun::Array(array))]);
+    }
+}
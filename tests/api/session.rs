@@ -0,0 +1,434 @@
+// Copyright 2022 Martin Pool
+
+//! Test the API for embedding the interpreter in a session.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use pretty_assertions::assert_eq;
+
+use rsj::atom::Atom;
+use rsj::error::{Error, Result};
+use rsj::eval::Session;
+use rsj::formatter::NounFormatter;
+use rsj::noun::Noun;
+use rsj::verb::Verb;
+use rsj::word::Word;
+
+/// A verb that doubles numbers, or as a dyad returns the count of atoms in
+/// both arguments.
+#[derive(Debug)]
+struct Double;
+
+impl Verb for Double {
+    fn display(&self) -> Cow<'_, str> {
+        "double.".into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        let atoms = y
+            .iter_atoms()
+            .map(|a| Ok(Atom::from(a.to_complex()? * 2.0)))
+            .collect::<Result<Vec<Atom>>>()?;
+        match y {
+            Noun::Atom(_) => Ok(Noun::Atom(atoms[0].clone())),
+            Noun::Array(_) => Ok(Noun::from(atoms)),
+        }
+    }
+
+    fn dyad(&self, x: &Noun, y: &Noun) -> Result<Noun> {
+        Ok(Noun::from(Atom::from(
+            x.iter_atoms().count() + y.iter_atoms().count(),
+        )))
+    }
+}
+
+/// A verb that's shown as `{`, but isn't the primitive.
+#[derive(Debug)]
+struct NotFrom;
+
+impl Verb for NotFrom {
+    fn display(&self) -> Cow<'_, str> {
+        "{".into()
+    }
+
+    fn monad(&self, y: &Noun) -> Result<Noun> {
+        Ok(y.clone())
+    }
+
+    fn dyad(&self, _x: &Noun, y: &Noun) -> Result<Noun> {
+        Ok(y.clone())
+    }
+}
+
+#[test]
+fn user_defined_verb() {
+    let mut session = Session::new();
+    session.define_verb("double.", Rc::new(Double));
+    assert_eq!(session.eval_text("double. 1 2 3"), "2 4 6");
+    assert_eq!(session.eval_text("1 2 double. 3"), "3");
+    assert_eq!(session.eval_text("double. i. 3"), "0 2 4");
+    assert_eq!(session.eval_text("double."), "double.");
+}
+
+#[test]
+fn user_defined_verb_without_inflection() {
+    let mut session = Session::new();
+    session.define_verb("double", Rc::new(Double));
+    assert_eq!(session.eval_text("+/ double 1 2"), "6");
+}
+
+#[test]
+fn user_defined_verb_with_underscores() {
+    let mut session = Session::new();
+    session.define_verb("my_double", Rc::new(Double));
+    session.define_verb("x_1", Rc::new(Double));
+    assert_eq!(session.eval_text("my_double 1 2"), "2 4");
+    assert_eq!(session.eval_text("x_1 _1 2"), "_2 4");
+    // A leading underscore starts a number, even after a name.
+    assert_eq!(session.eval_text("x_1 _1"), "_2");
+    // Underscores are part of the name, so this is a different, undefined,
+    // name.
+    assert_eq!(session.eval_text("x_1_1"), "error: Unexpected('x')");
+}
+
+#[test]
+fn assign_bonded_verb() {
+    let mut session = Session::new();
+    assert_eq!(session.eval_line("addten =: 10&+").unwrap(), None);
+    assert_eq!(session.eval_text("addten 5"), "15");
+    assert_eq!(session.eval_text("halve =: %&2"), "");
+    assert_eq!(session.eval_text("halve addten 4"), "7");
+    assert_eq!(session.defined_names(), ["addten", "halve"]);
+    // Reassigning replaces the old value.
+    session.eval_text("addten =: 20&+");
+    assert_eq!(session.eval_text("addten 5"), "25");
+}
+
+#[test]
+fn assignment_gives_no_result_in_eval_all() {
+    let mut session = Session::new();
+    let results = session.eval_all("a =: 2 + 3\na * 2");
+    assert!(matches!(results[0], Ok(None)));
+    assert_eq!(results[1].as_ref().unwrap(), &Some(Noun::from(10.0)));
+}
+
+#[test]
+fn eval_all_skips_shebang_line() {
+    let mut session = Session::new();
+    let results = session.eval_all("#!/usr/bin/env rsj\n1 + 2\n");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].as_ref().unwrap(), &Some(Noun::from(3.0)));
+    // Only on the first line.
+    let results = session.eval_all("1 + 2\n#!/usr/bin/env rsj\n");
+    assert_eq!(results.len(), 2);
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn explain_parse_shows_each_reduction() {
+    let mut session = Session::new();
+    assert_eq!(
+        session.explain_parse("(+/ % #) 2 $ 'ab'"),
+        [
+            "words: ( | + | / | % | # | ) | 2 | $ | 'ab'",
+            "adverb      ( | +/ | % | # | ) | 2 | $ | 'ab'",
+            "fork        ( | +/ % # | ) | 2 | $ | 'ab'",
+            "parens      +/ % # | 2 | $ | 'ab'",
+            "dyad        +/ % # | 'ab'",
+            "error: Domain",
+        ]
+    );
+    assert_eq!(
+        session.explain_parse("1 + 2 2 $ 3"),
+        [
+            "words: 1 | + | 2 2 | $ | 3",
+            "dyad        1 | + | (2 2 $ 3 3 3 3)",
+            "dyad        (2 2 $ 4 4 4 4)",
+        ]
+    );
+}
+
+#[test]
+fn explain_parse_changes_nothing_in_the_session() {
+    let mut session = Session::new();
+    session.set_random_seed(7);
+    let explained = session.explain_parse("a =: 10 ? 1000");
+    assert!(explained.last().unwrap().starts_with("assignment"));
+    assert_eq!(session.eval_text("a"), "error: Unexpected('a')");
+    assert_eq!(session.defined_names(), [] as [&str; 0]);
+    // The generator is where it was before explaining.
+    let first = session.eval_text("10 ? 1000");
+    session.set_random_seed(7);
+    assert_eq!(session.eval_text("10 ? 1000"), first);
+    session.set_random_seed(7);
+    session.explain_parse("10 ? 1000");
+    assert_eq!(session.eval_text("10 ? 1000"), first);
+}
+
+#[test]
+fn explicit_definition_uses_defined_verb() {
+    let mut session = Session::new();
+    session.define_verb("double", Rc::new(Double));
+    assert_eq!(session.eval_text("{{ 1 + double y }} 1 2 3"), "3 5 7");
+}
+
+#[test]
+fn explicit_definition_as_monad_and_dyad() {
+    let mut session = Session::new();
+    assert_eq!(session.eval_text("sub =: {{ x - y }}"), "");
+    assert_eq!(session.eval_text("10 sub 3"), "7");
+    assert_eq!(session.eval_text("sub 3"), "error: Valence");
+    assert_eq!(session.eval_text("inc =: {{ y + 1 }}"), "");
+    assert_eq!(session.eval_text("inc 3"), "4");
+    assert_eq!(session.eval_text("10 inc 3"), "error: Valence");
+    // Each can use the other.
+    assert_eq!(session.eval_text("2 {{ (inc x) sub y }} 1"), "2");
+}
+
+#[test]
+fn user_defined_verb_shadows_primitive() {
+    let mut session = Session::new();
+    session.define_verb("+", Rc::new(Double));
+    assert_eq!(session.eval_text("+ 10"), "20");
+    // Other sessions are not affected.
+    assert_eq!(Session::new().eval_text("1 + 10"), "11");
+}
+
+#[test]
+fn fit_applies_only_to_primitive_from() {
+    let mut session = Session::new();
+    assert_eq!(session.eval_text("5 {!.0 (1 2)"), "0");
+    session.define_verb("{", Rc::new(NotFrom));
+    assert_eq!(
+        session.eval_text("5 {!.0 (1 2)"),
+        "error: Unimplemented(\"{!.f fit\")"
+    );
+}
+
+#[test]
+fn undefined_name() {
+    let mut session = Session::new();
+    assert!(matches!(
+        session.eval_line("double 1"),
+        Err(Error::Unexpected('d'))
+    ));
+    assert!(matches!(
+        session.eval_line("double. 1"),
+        Err(Error::Unimplemented(_))
+    ));
+}
+
+#[test]
+fn box_at_rank_zero_boxes_each_atom() {
+    let mut session = Session::new();
+    let boxed = match session.eval_line("<\"0 i. 3").unwrap() {
+        Some(Word::Noun(Noun::Array(a))) => a,
+        other => panic!("unexpected result {:?}", other),
+    };
+    assert_eq!(boxed.dims(), &[3]);
+    for (i, atom) in boxed.iter_atoms().enumerate() {
+        assert_eq!(atom, &Atom::Boxed(Box::new(Noun::from(Atom::from(i)))));
+    }
+
+    match session.eval_line("< i. 3").unwrap() {
+        Some(Word::Noun(Noun::Atom(Atom::Boxed(contents)))) => {
+            assert_eq!(contents.dims(), &[3]);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn ascii_boxes() {
+    let mut session = Session::new();
+    assert_eq!(
+        session.eval_text("< < 'a'"),
+        "┌───┐\n│┌─┐│\n││a││\n│└─┘│\n└───┘"
+    );
+    session.set_ascii_boxes(true);
+    assert_eq!(
+        session.eval_text("< < 'a'"),
+        "+---+\n|+-+|\n||a||\n|+-+|\n+---+"
+    );
+    assert_eq!(session.eval_text("<\"0 'ab'"), "+-+-+\n|a|b|\n+-+-+");
+}
+
+/// Formats nouns as a list of their shape and atoms.
+#[derive(Debug)]
+struct ShapeFormatter;
+
+impl NounFormatter for ShapeFormatter {
+    fn format(&self, noun: &Noun, width: usize) -> String {
+        assert_eq!(width, 80);
+        format!("{:?} {}", noun.dims(), noun.iter_atoms().count())
+    }
+}
+
+#[test]
+fn custom_formatter() {
+    let mut session = Session::new();
+    session.set_formatter(Rc::new(ShapeFormatter));
+    assert_eq!(session.eval_text("2 3 $ i. 6"), "[2, 3] 6");
+    assert_eq!(session.eval_text("1 + 2"), "[] 1");
+    // Only nouns go through the formatter.
+    assert_eq!(session.eval_text("+/"), "+/");
+    assert_eq!(session.eval_text("1 % 'a'"), "error: Domain");
+    // Setting the box style goes back to the default formatter.
+    session.set_ascii_boxes(false);
+    assert_eq!(session.eval_text("2 3 $ i. 6"), "0 1 2\n3 4 5");
+}
+
+#[test]
+fn random_seed_repeats_numbers() {
+    let mut session = Session::new();
+    session.set_random_seed(7);
+    let first = session.eval_text("10 ? 1000");
+    assert_eq!(session.eval_text("10 ? 1000").split(' ').count(), 10);
+    session.set_random_seed(7);
+    assert_eq!(session.eval_text("10 ? 1000"), first);
+}
+
+#[test]
+fn random_numbers_belong_to_each_session() {
+    let mut a = Session::new();
+    let mut b = Session::new();
+    a.set_random_seed(7);
+    b.set_random_seed(7);
+    let first = a.eval_text("10 ? 1000");
+    // Drawing from one session, or reseeding it, doesn't move the other.
+    a.set_random_seed(8);
+    a.eval_text("10 ? 1000");
+    assert_eq!(b.eval_text("10 ? 1000"), first);
+    // Explicit definitions draw from the session that applies them.
+    b.set_random_seed(7);
+    b.eval_text("r =: {{ y ? 1000 }}");
+    assert_eq!(b.eval_text("r 10"), first);
+}
+
+#[test]
+fn non_converging_power_stops_at_iteration_limit() {
+    let mut session = Session::new();
+    assert_eq!(session.eval_text(">:^:_ (0)"), "error: Limit");
+    assert_eq!(session.eval_text("-:^:_ (1)"), "0");
+    session.set_max_iterations(10);
+    assert_eq!(session.eval_text("-:^:_ (1)"), "error: Limit");
+    assert_eq!(session.eval_text("<.^:_ (2.5)"), "2");
+}
+
+#[test]
+fn recursive_execute_stops_at_depth_limit() {
+    let mut session = Session::new();
+    session.eval_text("s =: '1 + \". s'");
+    assert_eq!(session.eval_text("\". s"), "error: Stack");
+    session.set_max_depth(2);
+    assert_eq!(session.eval_text("\". '\". ''1'''"), "1");
+    assert_eq!(
+        session.eval_text("\". '\". ''\". ''''1'''''''"),
+        "error: Stack"
+    );
+}
+
+#[test]
+fn limits_belong_to_each_session() {
+    let mut strict = Session::new();
+    strict.set_max_iterations(10);
+    strict.set_max_depth(1);
+    let mut other = Session::new();
+    assert_eq!(other.eval_text("-:^:_ (1)"), "0");
+    assert_eq!(other.eval_text("\". '\". ''1'''"), "1");
+    assert_eq!(strict.eval_text("-:^:_ (1)"), "error: Limit");
+    assert_eq!(strict.eval_text("\". '\". ''1'''"), "error: Stack");
+    // Explicit definitions run in a nested session, with the same limits.
+    strict.eval_text("f =: {{ -:^:_ y }}");
+    assert_eq!(strict.eval_text("f 1"), "error: Limit");
+}
+
+#[test]
+fn check_precision_of_large_integers() {
+    let mut session = Session::new();
+    // Without the check, large integers are silently rounded.
+    assert_eq!(session.eval_text("2 ^ 60"), "1.152921504606847e18");
+    session.set_check_precision(true);
+    assert_eq!(
+        session.eval_text("0 + 9007199254740991"),
+        "9007199254740991"
+    );
+    assert_eq!(session.eval_text("2 ^ 53"), "9007199254740992");
+    assert_eq!(session.eval_text("1 + 2 ^ 53"), "error: Precision");
+    assert_eq!(session.eval_text("_1 - 2 ^ 53"), "error: Precision");
+    assert_eq!(session.eval_text("+/ i. 100000"), "4999950000");
+    assert_eq!(session.eval_text("2 ^ 60"), "error: Precision");
+    // Literals are not checked, and infinities are not too large.
+    assert_eq!(session.eval_text("1e300"), "1e300");
+    assert_eq!(session.eval_text("_ + 1"), "_");
+}
+
+#[test]
+fn nested_sentences_inherit_options() {
+    let mut session = Session::new();
+    session.set_check_precision(true);
+    session.set_record_trace(true);
+    assert_eq!(session.eval_text("\". '2 ^ 60'"), "error: Precision");
+    assert_eq!(session.eval_text("{{ 2 ^ y }} 60"), "error: Precision");
+    session.take_trace();
+    assert_eq!(session.eval_text("{{ - y }} 2"), "_2");
+    assert_eq!(
+        session.take_trace(),
+        ["monad - (2) => _2", "monad {{ - y }} (2) => _2"]
+    );
+    // Other sessions still start from the defaults.
+    assert_eq!(
+        Session::new().eval_text("\". '2 ^ 60'"),
+        "1.152921504606847e18"
+    );
+}
+
+/// Numbers up to 2^53 are exact, and one more than that can't be represented.
+#[test]
+fn precision_check_at_the_exact_integer_limit() {
+    let mut session = Session::new();
+    session.set_check_precision(true);
+    assert_eq!(
+        session.eval_text("9007199254740991 + 1"),
+        "9007199254740992"
+    );
+    assert!(matches!(
+        session.eval_line("9007199254740991 + 2"),
+        Err(Error::Precision)
+    ));
+    assert!(matches!(session.eval_line("2 ^ 60"), Err(Error::Precision)));
+}
+
+#[test]
+fn eval_all_lines() {
+    let mut session = Session::new();
+    session.define_verb("double", Rc::new(Double));
+    let results = session.eval_all("1 + 2\n\n   NB. a comment\ndouble i. 3\n+/\n1 2 + 3 4 5\n");
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap(), &Some("3".parse().unwrap()));
+    assert_eq!(
+        results[1].as_ref().unwrap(),
+        &Some("0 2 4".parse().unwrap())
+    );
+    assert!(matches!(results[2], Ok(None)));
+    assert!(matches!(results[3], Err(Error::Length)));
+}
+
+#[test]
+fn eval_all_continues_after_errors() {
+    let mut session = Session::new();
+    session.define_verb("double", Rc::new(Double));
+    let results = session.eval_all(
+        "double 1\n1 % 'a'\n(1 + 2\n{{ y + 'a' }} 1\n\". '1 +'\ndouble 2\n{{ double y }} 3\n",
+    );
+    assert_eq!(results.len(), 7);
+    assert_eq!(results[0].as_ref().unwrap(), &Some("2".parse().unwrap()));
+    assert!(matches!(results[1], Err(Error::Domain)));
+    assert!(matches!(results[2], Err(Error::UnbalancedParens)));
+    assert!(matches!(results[3], Err(Error::Domain)));
+    assert!(matches!(results[4], Err(Error::SyntaxError)));
+    // Names defined before the errors are still there afterwards.
+    assert_eq!(results[5].as_ref().unwrap(), &Some("4".parse().unwrap()));
+    assert_eq!(results[6].as_ref().unwrap(), &Some("6".parse().unwrap()));
+}
@@ -1,3 +1,4 @@
 // Copyright 2021 Martin Pool
 
 mod markdown;
+mod options;
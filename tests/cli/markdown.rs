@@ -34,7 +34,9 @@ fn update_files_needing_update() {
             .arg("-M")
             .arg(&tmp_path)
             .assert()
-            .stderr(predicate::str::is_empty())
+            .stderr(
+                predicate::str::is_match(r"^updated [1-9]\d* of \d+ examples in .*\n$").unwrap(),
+            )
             .stdout(predicate::str::is_empty())
             .code(0);
         insta::with_settings!({snapshot_path => &snapshot_dir}, {
@@ -108,3 +110,167 @@ fn blog_files_are_up_to_date() {
         );
     }
 }
+
+#[test]
+fn check_counts_changed_examples() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("check.md");
+    fs::write(
+        &path,
+        "```\n   1 + 1\n2\n```\n\n```\n   2 + 2\n5\n```\n\n```\n   3 + 3\n```\n",
+    )
+    .unwrap();
+    let a = Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--check")
+        .arg(&path)
+        .assert()
+        .stderr("2 of 3 examples differ\n")
+        .code(2);
+    let stdout = String::from_utf8_lossy(&a.get_output().stdout).into_owned();
+    assert!(stdout.contains("+4\n"), "{}", stdout);
+    assert!(stdout.contains("+6\n"), "{}", stdout);
+}
+
+#[test]
+fn update_reports_changed_examples() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("update.md");
+    fs::write(
+        &path,
+        "```\n   1 + 1\n2\n```\n\n```\n   2 + 2\n5\n```\n\n```\n   3 + 3\n```\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("-M")
+        .arg(&path)
+        .assert()
+        .stdout(predicate::str::is_empty())
+        .stderr(format!("updated 2 of 3 examples in {}\n", path.display()))
+        .code(0);
+    assert_eq!(
+        read_to_string(&path).unwrap(),
+        "```\n   1 + 1\n2\n```\n\n```\n   2 + 2\n4\n```\n\n```\n   3 + 3\n6\n```\n"
+    );
+}
+
+#[test]
+fn update_without_backup() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("update.md");
+    fs::write(&path, "```\n   1 + 1\n3\n```\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("-M")
+        .arg(&path)
+        .arg("--no-backup")
+        .assert()
+        .stdout(predicate::str::is_empty())
+        .stderr(format!("updated 1 of 1 examples in {}\n", path.display()))
+        .code(0);
+    assert_eq!(read_to_string(&path).unwrap(), "```\n   1 + 1\n2\n```\n");
+    let backup_path = PathBuf::from(format!("{}.old", path.display()));
+    assert!(!backup_path.exists(), "{:?} exists", &backup_path);
+}
+
+#[test]
+fn update_keeps_crlf_line_breaks() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("crlf.md");
+    fs::write(&path, "# CRLF\r\n\r\n```\r\n   2 2 $ 1\r\n```\r\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("-M")
+        .arg(&path)
+        .arg("--no-backup")
+        .assert()
+        .code(0);
+    assert_eq!(
+        read_to_string(&path).unwrap(),
+        "# CRLF\r\n\r\n```\r\n   2 2 $ 1\r\n1 1\r\n1 1\r\n```\r\n"
+    );
+}
+
+#[test]
+fn check_up_to_date_file() {
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--check")
+        .arg("t/plus.md")
+        .assert()
+        .stdout(predicate::str::is_empty())
+        .stderr("0 of 1 examples differ\n")
+        .code(0);
+}
+
+#[test]
+fn keep_unimplemented_leaves_output_unchanged() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("unimplemented.md");
+    fs::write(
+        &path,
+        "```\n   </. 1 2\n┌─┬─┐\n│1│2│\n└─┴─┘\n   1 + 1\n3\n```\n",
+    )
+    .unwrap();
+    let a = Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--check")
+        .arg(&path)
+        .arg("--keep-unimplemented")
+        .assert()
+        .stderr("1 of 1 examples differ\n")
+        .code(1);
+    let stdout = String::from_utf8_lossy(&a.get_output().stdout).into_owned();
+    assert!(!stdout.contains("Unimplemented"), "{}", stdout);
+    assert!(stdout.contains("-3\n+2\n"), "{}", stdout);
+
+    // Without the option, the output is replaced by the error.
+    let a = Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("-D")
+        .arg(&path)
+        .assert()
+        .code(1);
+    let stdout = String::from_utf8_lossy(&a.get_output().stdout).into_owned();
+    assert!(
+        stdout.contains("+error: Unimplemented(\"oblique u/. y\")\n"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn trace_logs_verb_applications() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let md_path = tmpdir.path().join("trace.md");
+    fs::write(&md_path, "```\n   1 + - 2 3\n_1 _2\n```\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--trace")
+        .arg("-D")
+        .arg(&md_path)
+        .assert()
+        .stdout(predicate::str::is_empty())
+        .stderr("trace: monad - (2 3) => _2 _3\ntrace: dyad (1) + (_2 _3) => _1 _2\n")
+        .code(0);
+}
+
+#[test]
+fn explain_changed_sentences() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let md_path = tmpdir.path().join("explain.md");
+    fs::write(&md_path, "```\n   1 + 1\n2\n   - 2 + 3\n5\n```\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--explain")
+        .arg("-D")
+        .arg(&md_path)
+        .assert()
+        .stdout(predicate::str::contains("-5\n+_5\n"))
+        .stderr(
+            "   - 2 + 3\nexpected:\n5\noutput:\n_5\n\
+            trace: dyad (2) + (3) => 5\ntrace: monad - (5) => _5\n\n",
+        )
+        .code(1);
+}
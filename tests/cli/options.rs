@@ -0,0 +1,121 @@
+// Copyright 2022 Martin Pool
+
+//! Test general command line options.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn version() {
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(format!("rsj {}\n", env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn help_mentions_interactive_default() {
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "with no options, starts an interactive session",
+        ))
+        .stdout(predicate::str::contains("--version"));
+}
+
+#[test]
+fn repl_starts_after_profile_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let profile = dir.path().join("profile.ijs");
+    std::fs::write(&profile, "1 % 'a'\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--profile")
+        .arg(&profile)
+        .write_stdin("1 + 2\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("profile.ijs:1: error: Domain"))
+        .stdout(predicate::str::contains("3\n"));
+}
+
+#[test]
+fn repl_starts_without_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .env("RSJ_PROFILE", dir.path().join("missing.ijs"))
+        .write_stdin("1 + 2\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("missing.ijs"))
+        .stdout(predicate::str::contains("3\n"));
+}
+
+#[test]
+fn run_script_with_shebang() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("script.ijs");
+    std::fs::write(
+        &script,
+        "#!/usr/bin/env rsj\nNB. A comment\na =: 1 + 2\na * 10\n",
+    )
+    .unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg(&script)
+        .assert()
+        .success()
+        .stdout("30\n");
+}
+
+#[test]
+fn script_uses_session_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("script.ijs");
+    std::fs::write(&script, "- 2\n< 1\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--trace")
+        .arg(&script)
+        .assert()
+        .success()
+        .stderr("trace: monad - (2) => _2\ntrace: monad < (1) => ┌─┐\n│1│\n└─┘\n")
+        .stdout("_2\n┌─┐\n│1│\n└─┘\n");
+}
+
+#[test]
+fn script_errors_set_exit_status() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("script.ijs");
+    std::fs::write(&script, "1 % 'a'\n2 + 2\n").unwrap();
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg(&script)
+        .assert()
+        .code(1)
+        .stdout("error: Domain\n4\n");
+}
+
+#[test]
+fn ndjson_describes_each_line() {
+    Command::cargo_bin("rsj")
+        .unwrap()
+        .arg("--ndjson")
+        .write_stdin("a =: 1 + 2\na , 4\n1 % 'a'\n")
+        .assert()
+        .success()
+        .stdout(concat!(
+            r#"{"input":"a =: 1 + 2","output":"","error":null,"message":null}"#,
+            "\n",
+            r#"{"input":"a , 4","output":"3 4","error":null,"message":null}"#,
+            "\n",
+            r#"{"input":"1 % 'a'","output":null,"error":"Domain","message":"Domain"}"#,
+            "\n",
+        ));
+}